@@ -1,23 +1,42 @@
 use anyhow::Result;
-use csv_parser::CsvParser;
+use csv_parser::{CsvDocument, CsvParser};
+use parser_core::decompress::{decompress_reader, inner_extension};
 use parser_core::DocumentParser;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
+const USAGE: &str = concat!(
+    "Usage: csv-cli <fichier.csv | ->\n",
+    "       csv-cli validate <fichier.csv | -> [--examples N]\n",
+    "       csv-cli stats <fichier.csv | -> [--top N]"
+);
+
+/// Nombre d'anomalies détaillées par défaut dans le rapport de `validate`.
+const DEFAULT_EXAMPLES: usize = 10;
+
+/// Valeurs fréquentes listées par défaut par `stats`.
+const DEFAULT_TOP: usize = 10;
+
 fn main() -> Result<()> {
-    let path: PathBuf = std::env::args()
-        .nth(1)
-        .expect("Usage: csv-cli <fichier.csv>")
-        .into();
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase();
-    if ext != "csv" && ext != "tsv" {
-        eprintln!("Erreur : ce parser n'accepte que les fichiers .csv ou .tsv");
-        exit(2); // code 2 : mauvaise extension
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("validate") => return validate(&args[1..]),
+        Some("stats") => return stats(&args[1..]),
+        _ => {}
     }
+    let path: PathBuf = args.first().expect(USAGE).into();
+    // `-` : lecture en flux sur stdin (pipe, zcat…), sans contrôle d'extension
+    if path.as_os_str() == "-" {
+        for line in CsvParser::stream_reader(decompress_reader(std::io::stdin())?) {
+            if let Err(e) = line {
+                eprintln!("Erreur parsing CSV : {e}");
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+    check_extension(&path);
     match CsvParser::parse(&path) {
         Ok(_) => exit(0),
         Err(e) => {
@@ -26,3 +45,64 @@ fn main() -> Result<()> {
         }
     }
 }
+
+/// Quitte avec le code 2 si l'extension n'est pas `.csv` ou `.tsv`.
+fn check_extension(path: &Path) {
+    let ext = inner_extension(path);
+    if ext != "csv" && ext != "tsv" {
+        eprintln!("Erreur : ce parser n'accepte que les fichiers .csv ou .tsv");
+        exit(2); // code 2 : mauvaise extension
+    }
+}
+
+/// Fichier CSV complet (`-` : stdin, décompressé si besoin, chargé en mémoire).
+fn load(path: &Path) -> CsvDocument {
+    let doc = if path.as_os_str() == "-" {
+        let mut data = Vec::new();
+        decompress_reader(std::io::stdin())
+            .and_then(|mut r| Ok(r.read_to_end(&mut data)?))
+            .and_then(|_| CsvParser::parse_csv_bytes(data))
+    } else {
+        check_extension(path);
+        CsvParser::parse_csv(path)
+    };
+    doc.unwrap_or_else(|e| {
+        eprintln!("Erreur parsing CSV : {e}");
+        exit(1);
+    })
+}
+
+/// `validate` : rapport structurel sur stdout, code 3 si des anomalies sont trouvées.
+fn validate(args: &[String]) -> Result<()> {
+    let (mut path, mut examples) = (None, DEFAULT_EXAMPLES);
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--examples" => {
+                examples = it.next().and_then(|n| n.parse().ok()).expect(USAGE);
+            }
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => panic!("{USAGE}"),
+        }
+    }
+    let csv = load(&path.expect(USAGE));
+    let report = csv.validate(examples);
+    println!("{report}");
+    exit(if report.is_valid() { 0 } else { 3 }); // code 3 : CSV mal formé
+}
+
+/// `stats` : profil des colonnes, en JSON sur stdout.
+fn stats(args: &[String]) -> Result<()> {
+    let (mut path, mut top) = (None, DEFAULT_TOP);
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--top" => top = it.next().and_then(|n| n.parse().ok()).expect(USAGE),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => panic!("{USAGE}"),
+        }
+    }
+    let csv = load(&path.expect(USAGE));
+    println!("{}", serde_json::to_string_pretty(&csv.profile(top))?);
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use json_parser::JsonParser;
+use parser_core::decompress::{decompress_reader, inner_extension};
 use std::path::PathBuf;
 use std::process::exit;
 
@@ -8,13 +9,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _ = args.next(); // skip program name
     let path: PathBuf = args
         .next()
-        .expect("Usage: json-cli <fichier.json/jsonl>")
+        .expect("Usage: json-cli <fichier.json/jsonl | ->")
         .into();
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase();
+    // `-` : lecture en flux sur stdin (pipe, zcat…), sans contrôle d'extension
+    if path.as_os_str() == "-" {
+        for value in JsonParser::iter_reader(decompress_reader(std::io::stdin())?)? {
+            if let Err(e) = value {
+                eprintln!("Erreur parsing JSON : {e}");
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+    let ext = inner_extension(&path);
     if ext != "json" && ext != "jsonl" {
         eprintln!("Erreur : ce parser n'accepte que les fichiers .json ou .jsonl");
         exit(2); // code 2 : mauvaise extension
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use parser_core::decompress::decompress_reader;
 use parser_core::DocumentParser;
 use std::path::PathBuf;
 use txt_parser::TxtParser;
@@ -6,9 +7,16 @@ use txt_parser::TxtParser;
 fn main() -> Result<()> {
     let path: PathBuf = std::env::args()
         .nth(1)
-        .expect("Usage: parser-cli <fichier.txt>")
+        .expect("Usage: parser-cli <fichier.txt | ->")
         .into();
 
+    if path.as_os_str() == "-" {
+        // Lecture en flux sur stdin (décompressée si besoin) : mémoire bornée, une ligne à la fois
+        for line in TxtParser::stream_reader(decompress_reader(std::io::stdin())?) {
+            line?;
+        }
+        return Ok(());
+    }
     let _doc = TxtParser::parse(&path)?;
     Ok(())
 }
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use parser_core::decompress::{decompress_reader, inner_extension};
 use parser_core::DocumentParser;
 use std::path::PathBuf;
 use std::process::exit;
@@ -7,13 +8,19 @@ use txt_parser::TxtParser;
 fn main() -> Result<()> {
     let path: PathBuf = std::env::args()
         .nth(1)
-        .expect("Usage: txt-cli <fichier.txt>")
+        .expect("Usage: txt-cli <fichier.txt | ->")
         .into();
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_ascii_lowercase();
+    // `-` : lecture en flux sur stdin (pipe, zcat…), sans contrôle d'extension
+    if path.as_os_str() == "-" {
+        for line in TxtParser::stream_reader(decompress_reader(std::io::stdin())?) {
+            if let Err(e) = line {
+                eprintln!("Erreur parsing TXT : {e}");
+                exit(1);
+            }
+        }
+        exit(0);
+    }
+    let ext = inner_extension(&path);
     if ext != "txt" {
         eprintln!("Erreur : ce parser n'accepte que les fichiers .txt");
         exit(2); // code 2 : mauvaise extension
@@ -0,0 +1,418 @@
+//! Désérialisation serde des enregistrements CSV vers des types utilisateur.
+//!
+//! - Structs et maps : par nom de colonne si le document a un en-tête, sinon par position
+//! - Tuples, séquences : toujours par position
+//! - Un champ `&str` (ou `Cow<str>`) est emprunté au document, sauf s'il contient des `""`
+//! - Champ vide : `None` pour une `Option`
+
+use crate::document::{CsvDocument, Fields, Record};
+use parser_core::ParseError;
+use rayon::prelude::*;
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Erreur de désérialisation d'un enregistrement, avant localisation.
+#[derive(Debug)]
+pub(crate) struct DeError {
+    message: String,
+    /// Colonne et position (en octets dans l'enregistrement) du champ fautif.
+    field: Option<(usize, usize)>,
+}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError {
+            message: msg.to_string(),
+            field: None,
+        }
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl CsvDocument {
+    /// Enregistrement de données `i` désérialisé en `T`.
+    pub fn deserialize_record<'de, T: Deserialize<'de>>(
+        &'de self,
+        i: usize,
+    ) -> Result<T, ParseError> {
+        let record = self.record(i)?;
+        let by_name = self.dialect().has_header;
+        T::deserialize(RecordDeserializer::new(record, self.headers(), by_name)).map_err(|e| {
+            let (offset, message) = match e.field {
+                Some((j, offset)) => {
+                    let name = self.headers().get(j).map_or("", String::as_str);
+                    (
+                        offset,
+                        format!("field {name:?} (column {}): {}", j + 1, e.message),
+                    )
+                }
+                None => (0, e.message),
+            };
+            self.record_error(i, offset, message)
+        })
+    }
+
+    /// Itérateur sur les enregistrements de données désérialisés en `T`.
+    pub fn deserialize<'de, T: Deserialize<'de> + 'de>(
+        &'de self,
+    ) -> impl Iterator<Item = Result<T, ParseError>> + 'de {
+        (0..self.record_count()).map(move |i| self.deserialize_record(i))
+    }
+
+    /// Variante parallèle (rayon) de [`deserialize`](Self::deserialize), ordre conservé.
+    pub fn par_deserialize<'de, T: Deserialize<'de> + Send + 'de>(
+        &'de self,
+    ) -> impl IndexedParallelIterator<Item = Result<T, ParseError>> + 'de {
+        (0..self.record_count())
+            .into_par_iter()
+            .map(move |i| self.deserialize_record(i))
+    }
+}
+
+/// Désérialiseur d'un enregistrement : map (nom de colonne → champ) ou séquence.
+struct RecordDeserializer<'de> {
+    fields: Fields<'de>,
+    len: usize,
+    headers: &'de [String],
+    by_name: bool,
+    column: usize,
+    /// Champ dont la clé vient d'être lue (mode map).
+    value: Option<(Cow<'de, str>, usize)>,
+}
+
+impl<'de> RecordDeserializer<'de> {
+    fn new(record: Record<'de>, headers: &'de [String], by_name: bool) -> Self {
+        RecordDeserializer {
+            fields: record.fields(),
+            len: record.as_str().len(),
+            headers,
+            by_name,
+            column: 0,
+            value: None,
+        }
+    }
+
+    /// Champ suivant et sa position dans l'enregistrement.
+    fn next_field(&mut self) -> Option<(Cow<'de, str>, usize)> {
+        let offset = self.len - self.fields.remaining();
+        let value = self.fields.next()?;
+        Some((value, offset))
+    }
+
+    /// Désérialise un champ en rattachant l'erreur éventuelle à sa colonne.
+    fn field<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+        (value, offset): (Cow<'de, str>, usize),
+    ) -> Result<T::Value, DeError> {
+        let column = self.column;
+        self.column += 1;
+        seed.deserialize(FieldDeserializer(value))
+            .map_err(|e| DeError {
+                field: e.field.or(Some((column, offset))),
+                ..e
+            })
+    }
+
+    /// Enregistrement à une seule valeur (`T` scalaire) : premier champ.
+    fn single(mut self) -> Result<FieldDeserializer<'de>, DeError> {
+        let (value, _) = self
+            .next_field()
+            .ok_or_else(|| de::Error::custom("empty record"))?;
+        Ok(FieldDeserializer(value))
+    }
+}
+
+macro_rules! forward_to_first_field {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for RecordDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.by_name {
+            visitor.visit_map(self)
+        } else {
+            visitor.visit_seq(self)
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_first_field! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_identifier
+    }
+}
+
+impl<'de> SeqAccess<'de> for RecordDeserializer<'de> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        match self.next_field() {
+            Some(field) => self.field(seed, field).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for RecordDeserializer<'de> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        // Champs au-delà de l'en-tête : ignorés.
+        let Some(name) = self.headers.get(self.column) else {
+            return Ok(None);
+        };
+        let Some(field) = self.next_field() else {
+            return Ok(None);
+        };
+        self.value = Some(field);
+        seed.deserialize(BorrowedStrDeserializer::new(name.as_str()))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let field = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        self.field(seed, field)
+    }
+}
+
+/// Désérialiseur d'un champ : texte converti selon le type demandé.
+struct FieldDeserializer<'de>(Cow<'de, str>);
+
+impl FieldDeserializer<'_> {
+    fn parse<T>(&self, ty: &str) -> Result<T, DeError>
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        self.0
+            .trim()
+            .parse()
+            .map_err(|e| de::Error::custom(format!("invalid {ty} {:?}: {e}", self.0)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident $ty:ty,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0.trim() {
+            v if v.eq_ignore_ascii_case("true") => visitor.visit_bool(true),
+            v if v.eq_ignore_ascii_case("false") => visitor.visit_bool(false),
+            v => Err(de::Error::custom(format!("invalid bool {v:?}"))),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8 i8,
+        deserialize_i16 => visit_i16 i16,
+        deserialize_i32 => visit_i32 i32,
+        deserialize_i64 => visit_i64 i64,
+        deserialize_i128 => visit_i128 i128,
+        deserialize_u8 => visit_u8 u8,
+        deserialize_u16 => visit_u16 u16,
+        deserialize_u32 => visit_u32 u32,
+        deserialize_u64 => visit_u64 u64,
+        deserialize_u128 => visit_u128 u128,
+        deserialize_f32 => visit_f32 f32,
+        deserialize_f64 => visit_f64 f64,
+        deserialize_char => visit_char char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            Cow::Borrowed(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+            Cow::Owned(s) => visitor.visit_byte_buf(s.into_bytes()),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.0.is_empty() {
+            visitor.visit_unit()
+        } else {
+            Err(de::Error::custom(format!(
+                "expected empty field, got {:?}",
+                self.0
+            )))
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        // Variantes unitaires seulement : le champ est le nom de la variante.
+        match self.0 {
+            Cow::Borrowed(s) => {
+                BorrowedStrDeserializer::new(s).deserialize_enum(name, variants, visitor)
+            }
+            Cow::Owned(s) => StringDeserializer::new(s).deserialize_enum(name, variants, visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        seq tuple tuple_struct map struct
+    }
+}
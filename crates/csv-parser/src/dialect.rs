@@ -0,0 +1,190 @@
+//! Dialecte CSV (séparateur, guillemet, en-tête) et détection automatique.
+//!
+//! La détection travaille sur un échantillon de tête :
+//! - séparateur : nombre de champs le plus régulier d'un enregistrement à l'autre
+//! - guillemet : `"` sauf si seul `'` encadre des champs
+//! - en-tête : première ligne dont les types diffèrent de ceux des lignes suivantes
+
+use crate::document::Fields;
+use crate::records::index_records;
+use crate::schema::ColumnType;
+use parser_core::options::LineTerminator;
+
+/// Taille de l'échantillon analysé (64 KiB).
+const SAMPLE_LEN: usize = 64 * 1024;
+/// Nombre maximal d'enregistrements examinés.
+const SAMPLE_RECORDS: usize = 200;
+/// Séparateurs essayés par défaut, par ordre de préférence à score égal.
+pub const DEFAULT_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Dialecte d'un fichier CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect {
+    /// Séparateur de champs (ASCII).
+    pub delimiter: u8,
+    /// Caractère de citation (ASCII).
+    pub quote: u8,
+    /// Vrai si le premier enregistrement est une ligne d'en-tête.
+    pub has_header: bool,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect {
+            delimiter: b',',
+            quote: b'"',
+            has_header: true,
+        }
+    }
+}
+
+/// Type d'une cellule pour comparer l'en-tête aux données (entiers et flottants confondus).
+fn cell_kind(s: &str) -> Option<ColumnType> {
+    ColumnType::of(s).map(|t| if t.is_numeric() { ColumnType::Float } else { t })
+}
+
+impl Dialect {
+    /// Dialecte par défaut : `,`, `"`, avec en-tête.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Détecte le dialecte parmi les séparateurs usuels (`,` `;` `\t` `|`).
+    pub fn sniff(data: &[u8]) -> Self {
+        Self::sniff_with(data, &DEFAULT_DELIMITERS)
+    }
+
+    /// Détecte le dialecte parmi les séparateurs `candidates` (ASCII).
+    pub fn sniff_with(data: &[u8], candidates: &[u8]) -> Self {
+        let truncated = data.len() > SAMPLE_LEN;
+        let sample = &data[..data.len().min(SAMPLE_LEN)];
+        let quote = Self::sniff_quote(sample, candidates);
+        let records = sample_records(sample, quote, truncated);
+        let delimiter = candidates
+            .iter()
+            .copied()
+            .filter(u8::is_ascii)
+            .map(|d| (d, delimiter_score(&records, d, quote)))
+            .fold(None, |best: Option<(u8, f64)>, (d, score)| match best {
+                Some((_, s)) if s >= score => best,
+                _ if score > 0.0 => Some((d, score)),
+                _ => best,
+            })
+            .map_or(b',', |(d, _)| d);
+        Dialect {
+            delimiter,
+            quote,
+            has_header: sniff_header(&records, delimiter, quote),
+        }
+    }
+
+    /// `'` si des apostrophes encadrent des champs et qu'il n'y a aucun `"`, sinon `"`.
+    fn sniff_quote(sample: &[u8], candidates: &[u8]) -> u8 {
+        if memchr::memchr(b'"', sample).is_some() {
+            return b'"';
+        }
+        let boundary =
+            |b: Option<&u8>| b.is_none_or(|b| *b == b'\n' || *b == b'\r' || candidates.contains(b));
+        let framed = memchr::memchr_iter(b'\'', sample)
+            .filter(|&i| boundary(i.checked_sub(1).and_then(|p| sample.get(p))))
+            .any(|open| {
+                memchr::memchr(b'\'', &sample[open + 1..])
+                    .is_some_and(|p| boundary(sample.get(open + 1 + p + 1)))
+            });
+        if framed {
+            b'\''
+        } else {
+            b'"'
+        }
+    }
+}
+
+/// Enregistrements non vides de l'échantillon (le dernier est écarté s'il est tronqué).
+fn sample_records(sample: &[u8], quote: u8, truncated: bool) -> Vec<&str> {
+    let index = index_records(sample, 0, sample.len().max(1), LineTerminator::Auto, quote);
+    let mut records: Vec<&str> = index
+        .iter()
+        .filter_map(|(s, l)| std::str::from_utf8(&sample[s as usize..(s + l) as usize]).ok())
+        .filter(|r| !r.is_empty())
+        .take(SAMPLE_RECORDS + 1)
+        .collect();
+    if truncated || records.len() > SAMPLE_RECORDS {
+        records.pop();
+    }
+    records
+}
+
+/// Score d'un séparateur : part des enregistrements ayant le nombre de champs le plus
+/// fréquent (au moins 2), légèrement favorisé quand ce nombre est grand.
+fn delimiter_score(records: &[&str], delimiter: u8, quote: u8) -> f64 {
+    if records.is_empty() {
+        return 0.0;
+    }
+    let mut counts = std::collections::HashMap::new();
+    for r in records {
+        *counts
+            .entry(Fields::new(r, delimiter, quote).count())
+            .or_insert(0usize) += 1;
+    }
+    let (fields, freq) = counts
+        .into_iter()
+        .max_by_key(|&(fields, freq)| (freq, fields))
+        .unwrap();
+    if fields < 2 {
+        return 0.0;
+    }
+    freq as f64 / records.len() as f64 + (fields as f64).ln() * 1e-3
+}
+
+/// Vote colonne par colonne : une colonne dont les données ont un type (ou une longueur)
+/// constant et dont la première cellule s'en écarte vote pour un en-tête.
+///
+/// Sans indice contraire, on garde l'en-tête (comportement historique).
+fn sniff_header(records: &[&str], delimiter: u8, quote: u8) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return true;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    let header: Vec<_> = Fields::new(first, delimiter, quote).collect();
+    let rows: Vec<Vec<_>> = rest
+        .iter()
+        .map(|r| Fields::new(r, delimiter, quote).collect())
+        .filter(|row: &Vec<_>| row.len() == header.len())
+        .collect();
+    if rows.is_empty() {
+        return true;
+    }
+    let mut votes = 0i32;
+    for (j, head) in header.iter().enumerate() {
+        let kinds: Vec<ColumnType> = rows.iter().filter_map(|row| cell_kind(&row[j])).collect();
+        let Some(&kind) = kinds.first() else {
+            continue;
+        };
+        if kinds.iter().all(|k| *k == kind) && kind != ColumnType::String {
+            votes += if cell_kind(head) == Some(kind) { -1 } else { 1 };
+            continue;
+        }
+        let len = rows[0][j].chars().count();
+        if rows.iter().all(|row| row[j].chars().count() == len) {
+            votes += if head.chars().count() == len { -1 } else { 1 };
+        }
+    }
+    votes >= 0
+}
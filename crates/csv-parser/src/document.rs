@@ -0,0 +1,332 @@
+//! Accès champ par champ aux enregistrements d'un `Document` CSV.
+//!
+//! - Zéro-copy : un champ est un `&str` du mmap, sauf s'il faut retirer des `""`
+//! - Avec en-tête (voir [`Dialect`]), accès par nom de colonne
+
+use crate::schema::{dedup_names, ColumnState, Schema};
+use crate::Dialect;
+use parser_core::{Document, Location, ParseError};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Document CSV : `Document` indexé par enregistrement + découpage en champs.
+pub struct CsvDocument {
+    doc: Document,
+    dialect: Dialect,
+    headers: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl CsvDocument {
+    /// Enveloppe un document dont chaque « ligne » est un enregistrement CSV.
+    ///
+    /// Le séparateur et le guillemet du dialecte doivent être des octets ASCII.
+    pub fn new(doc: Document, dialect: Dialect) -> Result<Self, ParseError> {
+        if !dialect.delimiter.is_ascii() || !dialect.quote.is_ascii() {
+            return Err(ParseError::Format(
+                "delimiter and quote must be ASCII".to_string(),
+            ));
+        }
+        let first = match doc.line_count() {
+            0 => None,
+            _ => Some(Fields::new(
+                doc.get_line(0)?,
+                dialect.delimiter,
+                dialect.quote,
+            )),
+        };
+        let headers = match first {
+            Some(fields) if dialect.has_header => dedup_names(fields.map(Cow::into_owned)),
+            // Sans en-tête : column_1, column_2…
+            Some(fields) => dedup_names(fields.map(|_| String::new())),
+            None => Vec::new(),
+        };
+        Ok(CsvDocument {
+            doc,
+            dialect,
+            headers,
+            path: None,
+        })
+    }
+
+    /// Document sous-jacent (ligne 0 = en-tête s'il y en a un).
+    pub fn document(&self) -> &Document {
+        &self.doc
+    }
+
+    /// Dialecte utilisé (détecté ou imposé).
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Change la présence d'une ligne d'en-tête (sans réindexer).
+    pub fn with_header(self, has_header: bool) -> Result<Self, ParseError> {
+        let dialect = self.dialect.has_header(has_header);
+        let path = self.path;
+        Ok(CsvDocument {
+            path,
+            ..Self::new(self.doc, dialect)?
+        })
+    }
+
+    /// Noms de colonnes uniques : en-tête dédupliqué (`nom`, `nom_2`…, vides en
+    /// `column_<n>`), ou `column_1`… sans en-tête.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Position de la colonne `name` dans l'en-tête.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|h| h == name)
+    }
+
+    /// Lignes d'en-tête précédant les données (0 ou 1).
+    pub(crate) fn header_lines(&self) -> usize {
+        (self.dialect.has_header && self.doc.line_count() > 0) as usize
+    }
+
+    /// Nombre d'enregistrements de données (en-tête exclu).
+    pub fn record_count(&self) -> usize {
+        self.doc.line_count() - self.header_lines()
+    }
+
+    /// Enregistrement de données `i` (en-tête exclu).
+    pub fn record(&self, i: usize) -> Result<Record<'_>, ParseError> {
+        let line = self
+            .doc
+            .get_line(
+                i.checked_add(self.header_lines())
+                    .ok_or(ParseError::Index(i))?,
+            )
+            .map_err(|e| match e {
+                ParseError::Index(_) => ParseError::Index(i),
+                e => e,
+            })?;
+        Ok(Record {
+            raw: line,
+            delimiter: self.dialect.delimiter,
+            quote: self.dialect.quote,
+        })
+    }
+
+    /// Champs de l'enregistrement `i`.
+    pub fn fields(&self, i: usize) -> Result<Fields<'_>, ParseError> {
+        Ok(self.record(i)?.fields())
+    }
+
+    /// Champ `j` de l'enregistrement `i`.
+    pub fn field(&self, i: usize, j: usize) -> Result<Cow<'_, str>, ParseError> {
+        self.record(i)?.get(j).ok_or(ParseError::Index(j))
+    }
+
+    /// Champ de la colonne `name` dans l'enregistrement `i`.
+    pub fn field_by_name(&self, i: usize, name: &str) -> Result<Cow<'_, str>, ParseError> {
+        let j = self
+            .column_index(name)
+            .ok_or_else(|| ParseError::Format(format!("unknown column: {name}")))?;
+        self.field(i, j)
+    }
+
+    /// Renseigne le fichier source des erreurs signalées par le document.
+    pub(crate) fn with_path(mut self, path: &Path) -> Self {
+        self.doc = self.doc.with_path(path);
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Erreur de format localisée à l'octet `offset` de l'enregistrement de données `i`.
+    pub(crate) fn record_error(&self, i: usize, offset: usize, message: String) -> ParseError {
+        let line = i + self.header_lines();
+        let (start, len) = self.doc.offsets.get(line).unwrap_or_default();
+        let bytes = &self.doc.data.as_bytes()[start as usize..(start + len) as usize];
+        let mut location = Location::in_line(bytes, line, start, start + offset as u64);
+        location.path = self.path.clone();
+        ParseError::InvalidLine {
+            location: Box::new(location),
+            message,
+        }
+    }
+
+    /// Infère le schéma sur les `sample` premiers enregistrements (`None` : tout le fichier),
+    /// en parallèle. Les enregistrements illisibles (UTF-8 invalide) sont ignorés.
+    pub fn infer_schema(&self, sample: Option<usize>) -> Schema {
+        let n = sample.map_or(self.record_count(), |s| s.min(self.record_count()));
+        let width = self.headers.len();
+        let states = (0..n)
+            .into_par_iter()
+            .with_min_len(1024)
+            .fold(
+                || vec![ColumnState::default(); width],
+                |mut states, i| {
+                    if let Ok(record) = self.record(i) {
+                        let mut fields = record.fields();
+                        for state in states.iter_mut() {
+                            state.observe(fields.next().as_deref());
+                        }
+                    }
+                    states
+                },
+            )
+            .reduce(
+                || vec![ColumnState::default(); width],
+                |a, b| a.into_iter().zip(b).map(|(x, y)| x.merge(y)).collect(),
+            );
+        Schema {
+            columns: states
+                .into_iter()
+                .zip(&self.headers)
+                .map(|(state, name)| state.into_column(name.clone()))
+                .collect(),
+        }
+    }
+
+    /// Itérateur sur les enregistrements de données.
+    pub fn records(&self) -> impl Iterator<Item = Result<Record<'_>, ParseError>> {
+        (0..self.record_count()).map(move |i| self.record(i))
+    }
+}
+
+/// Enregistrement CSV emprunté au document.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    raw: &'a str,
+    pub(crate) delimiter: u8,
+    pub(crate) quote: u8,
+}
+
+impl<'a> Record<'a> {
+    /// Texte brut de l'enregistrement (guillemets compris).
+    pub fn as_str(&self) -> &'a str {
+        self.raw
+    }
+
+    /// Itérateur sur les champs.
+    pub fn fields(&self) -> Fields<'a> {
+        Fields::new(self.raw, self.delimiter, self.quote)
+    }
+
+    /// Champ `j`, s'il existe.
+    pub fn get(&self, j: usize) -> Option<Cow<'a, str>> {
+        self.fields().nth(j)
+    }
+
+    /// Nombre de champs.
+    pub fn len(&self) -> usize {
+        self.fields().count()
+    }
+
+    /// Vrai si [`Record::len`] vaut 0, ce qui n'arrive jamais : un enregistrement
+    /// vide a un champ vide. Voir [`Record::is_blank`].
+    pub fn is_empty(&self) -> bool {
+        self.fields().next().is_none()
+    }
+
+    /// Vrai si l'enregistrement ne contient aucun caractère (ligne vide).
+    pub fn is_blank(&self) -> bool {
+        self.raw.is_empty()
+    }
+}
+
+/// Itérateur sur les champs d'un enregistrement.
+///
+/// Champ entre guillemets : guillemets retirés, `""` remplacé par `"` (alors seulement
+/// alloué). Le texte suivant un guillemet fermant, jusqu'au séparateur, est conservé.
+#[derive(Debug, Clone)]
+pub struct Fields<'a> {
+    rest: Option<&'a str>,
+    delimiter: u8,
+    quote: u8,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(record: &'a str, delimiter: u8, quote: u8) -> Self {
+        Fields {
+            rest: Some(record),
+            delimiter,
+            quote,
+        }
+    }
+
+    /// Octets restant à découper (0 après le dernier champ).
+    pub(crate) fn remaining(&self) -> usize {
+        self.rest.map_or(0, str::len)
+    }
+
+    /// Champ suivant tel qu'écrit dans l'enregistrement (guillemets et `""` compris),
+    /// sans construire sa valeur.
+    pub fn next_raw(&mut self) -> Option<&'a str> {
+        let s = self.rest?;
+        let bytes = s.as_bytes();
+        let mut after = 0;
+        if bytes.first() == Some(&self.quote) {
+            let mut i = 1;
+            after = loop {
+                match memchr::memchr(self.quote, &bytes[i..]) {
+                    Some(p) if bytes.get(i + p + 1) == Some(&self.quote) => i += p + 2,
+                    Some(p) => break i + p + 1,
+                    None => break bytes.len(),
+                }
+            };
+        }
+        let end = memchr::memchr(self.delimiter, &bytes[after..]).map_or(s.len(), |p| after + p);
+        self.rest = (end < s.len()).then(|| &s[end + 1..]);
+        Some(&s[..end])
+    }
+
+    /// Champ entre guillemets en tête de `s` (guillemet ouvrant inclus) ;
+    /// renvoie la valeur et la position après le champ.
+    fn quoted(&self, s: &'a str) -> (Cow<'a, str>, usize) {
+        let bytes = s.as_bytes();
+        let mut value: Option<String> = None;
+        let mut seg = 1;
+        let mut i = 1;
+        let close = loop {
+            match memchr::memchr(self.quote, &bytes[i..]) {
+                Some(p) if bytes.get(i + p + 1) == Some(&self.quote) => {
+                    // `""` : guillemet littéral
+                    let v = value.get_or_insert_with(String::new);
+                    v.push_str(&s[seg..=i + p]);
+                    i += p + 2;
+                    seg = i;
+                }
+                Some(p) => break Some(i + p),
+                None => break None,
+            }
+        };
+        let (inner_end, after) = match close {
+            Some(c) => (c, c + 1),
+            // Guillemet non refermé : le champ court jusqu'à la fin.
+            None => (bytes.len(), bytes.len()),
+        };
+        let tail_end =
+            memchr::memchr(self.delimiter, &bytes[after..]).map_or(bytes.len(), |p| after + p);
+        let value = match value {
+            None if tail_end == after => Cow::Borrowed(&s[1..inner_end]),
+            v => {
+                let mut v = v.unwrap_or_default();
+                v.push_str(&s[seg..inner_end]);
+                v.push_str(&s[after..tail_end]);
+                Cow::Owned(v)
+            }
+        };
+        (value, tail_end)
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.rest?;
+        let (value, end) = if s.as_bytes().first() == Some(&self.quote) {
+            self.quoted(s)
+        } else {
+            let end = memchr::memchr(self.delimiter, s.as_bytes()).unwrap_or(s.len());
+            (Cow::Borrowed(&s[..end]), end)
+        };
+        // Séparateur ASCII : `end + 1` reste sur une frontière de caractère.
+        self.rest = (end < s.len()).then(|| &s[end + 1..]);
+        Some(value)
+    }
+}
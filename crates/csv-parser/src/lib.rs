@@ -4,149 +4,193 @@
 //! - Indexation rapide des lignes et colonnes (offsets)
 //! - Parallélisation rayon
 //! - API unifiée (Document)
-//! - Support CSV (,) et TSV (\t) auto-détecté
+//! - Dialecte auto-détecté (`,` `;` `\t` `|`, guillemet, en-tête) ou imposé
+//! - Champs entre guillemets (RFC 4180) : fins de ligne et `""` dans les champs
+//! - En-tête dédupliqué et schéma inféré en parallèle (int, float, bool, date, string)
+//! - Désérialisation serde (séquentielle ou rayon) par nom de colonne ou position
+//! - Projection sur quelques colonnes (champs inutiles sautés sans être construits)
+//! - Profil statistique des colonnes (min/max/moyenne, longueurs, distincts HyperLogLog, top-k)
+//! - Validation structurelle (champs irréguliers, guillemets) avec rapport détaillé
+//! - Écriture CSV bufferisée (`CsvWriter`) : dialecte conservé, champs bruts recopiés, serde
 //! - Pagination, stride, validation optionnelle
 
-use memchr::memchr_iter;
-use memmap2::MmapOptions;
-use parser_core::{Document, DocumentData, DocumentParser, ParseError};
-use rayon::prelude::*;
-use std::{fs::File, path::Path, sync::Arc};
+use parser_core::options::{LineTerminator, Validation};
+use parser_core::{decompress, encoding};
+use parser_core::{
+    Document, DocumentData, DocumentParser, IndexTag, LineIndex, LineStream, ParseError,
+    ParseOptions, SparseDocument,
+};
+use std::io::{BufReader, Read};
+use std::{path::Path, sync::Arc};
 
-const CHUNK_SIZE: usize = 64 * 1024 * 1024;
-const AVG_LINE_LEN: usize = 40;
+mod de;
+mod dialect;
+mod document;
+mod projection;
+mod records;
+mod schema;
+mod stats;
+mod validate;
+mod writer;
+
+pub use dialect::{Dialect, DEFAULT_DELIMITERS};
+pub use document::{CsvDocument, Fields, Record};
+pub use projection::{ColumnRef, Projection};
+pub use schema::{dedup_names, Column, ColumnType, Schema};
+pub use stats::{ColumnStats, LengthStats, NumericStats, Profile, TopValue};
+pub use validate::{Issue, IssueKind, ValidationReport};
+pub use writer::CsvWriter;
+
+/// Caractère de citation RFC 4180.
+const QUOTE: u8 = b'"';
 
 pub struct CsvParser;
 
 impl CsvParser {
-    /// Détecte le séparateur (CSV ou TSV) sur les premières lignes.
-    #[allow(dead_code)]
-    fn detect_separator(data: &[u8]) -> u8 {
-        let sample = &data[..data.len().min(4096)];
-        let csv = sample.iter().filter(|&&b| b == b',').count();
-        let tsv = sample.iter().filter(|&&b| b == b'\t').count();
-        if tsv > csv {
-            b'\t'
+    /// Indexe les enregistrements par chunks d'environ `chunk_size` octets.
+    ///
+    /// Les champs entre guillemets peuvent contenir des fins de ligne (RFC 4180) ;
+    /// le résultat ne dépend pas de `chunk_size` (identique à un scan séquentiel).
+    pub fn compute_offsets_chunked(data: &[u8], chunk_size: usize) -> LineIndex {
+        records::index_records(data, 0, chunk_size, LineTerminator::Auto, QUOTE)
+    }
+
+    /// Construit le document : une « ligne » du `Document` est un enregistrement CSV.
+    fn build(data: DocumentData, opts: &ParseOptions) -> Result<Document, ParseError> {
+        Document::from_data_with(data, opts, |bytes, bom| {
+            records::index_records(bytes, bom, opts.chunk_size, opts.line_terminator, QUOTE)
+        })
+    }
+
+    /// Options correspondant au drapeau historique `validate_utf8`.
+    fn validation_options(validate_utf8: bool) -> ParseOptions {
+        ParseOptions::new().validation(if validate_utf8 {
+            Validation::Strict
         } else {
-            b','
-        }
+            Validation::Off
+        })
     }
 
-    /// Indexe les offsets (start, len) de chaque ligne.
-    fn compute_offsets(data: &[u8], stride: usize) -> Vec<(u32, u32)> {
-        let len = data.len();
-        if len == 0 {
-            return Vec::new();
-        }
-        let boundaries: Vec<(usize, usize)> = (0..len)
-            .step_by(CHUNK_SIZE)
-            .map(|s| (s, (s + CHUNK_SIZE).min(len)))
-            .collect();
-        let total_est = len / AVG_LINE_LEN;
-        let per_chunk = (total_est / boundaries.len()).max(1);
-        boundaries
-            .into_par_iter()
-            .fold(
-                || Vec::with_capacity(per_chunk),
-                |mut local, (s, e)| {
-                    let slice = &data[s..e];
-                    let mut prev = 0;
-                    let mut idx = 0;
-                    for pos in memchr_iter(b'\n', slice) {
-                        if idx % stride == 0 {
-                            // On retire le \n du calcul de longueur
-                            let line_end = if pos > 0 && slice[pos - 1] == b'\r' {
-                                pos - 1
-                            } else {
-                                pos
-                            };
-                            local.push(((s + prev) as u32, (line_end - prev) as u32));
-                        }
-                        prev = pos + 1;
-                        idx += 1;
-                    }
-                    if prev < slice.len() && idx % stride == 0 {
-                        local.push(((s + prev) as u32, (slice.len() - prev) as u32));
-                    }
-                    local
-                },
-            )
-            .reduce(Vec::new, |mut acc, mut local| {
-                acc.append(&mut local);
-                acc
-            })
+    /// Parse un fichier CSV/TSV avec accès par champ et par nom de colonne.
+    pub fn parse_csv(path: &Path) -> Result<CsvDocument, ParseError> {
+        Self::parse_csv_with(path, &ParseOptions::default())
+    }
+
+    /// Comme [`CsvParser::parse_csv`], avec options ; le dialecte est détecté.
+    pub fn parse_csv_with(path: &Path, opts: &ParseOptions) -> Result<CsvDocument, ParseError> {
+        Self::parse_csv_dialect(path, opts, None)
+    }
+
+    /// Parse un fichier CSV avec un dialecte imposé (`None` : détection automatique).
+    pub fn parse_csv_dialect(
+        path: &Path,
+        opts: &ParseOptions,
+        dialect: Option<Dialect>,
+    ) -> Result<CsvDocument, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        Self::build_csv(backing, opts, dialect)
+            .map(|csv| csv.with_path(path))
+            .map_err(|e| e.with_path(path))
     }
 
-    /// Validation UTF-8 stricte sur toutes les lignes.
-    fn validate(data: &[u8], offsets: &[(u32, u32)], _full: bool) -> Result<(), ParseError> {
-        for &(st, ln) in offsets {
-            let slice = &data[st as usize..(st + ln) as usize];
-            std::str::from_utf8(slice)?;
-        }
-        Ok(())
+    /// Parse un buffer mémoire avec accès par champ (dialecte détecté).
+    pub fn parse_csv_bytes(data: Vec<u8>) -> Result<CsvDocument, ParseError> {
+        Self::build_csv(
+            DocumentData::Buffer(Arc::new(data)),
+            &ParseOptions::default(),
+            None,
+        )
     }
 
-    /// Parse un fichier CSV/TSV via mmap, avec validation optionnelle.
+    /// Indexe avec le guillemet du dialecte, détecté sur les données UTF-8 si absent.
+    fn build_csv(
+        data: DocumentData,
+        opts: &ParseOptions,
+        dialect: Option<Dialect>,
+    ) -> Result<CsvDocument, ParseError> {
+        let mut chosen = dialect;
+        let doc = Document::from_data_with(data, opts, |bytes, bom| {
+            let d = *chosen.get_or_insert_with(|| Dialect::sniff(&bytes[bom..]));
+            records::index_records(bytes, bom, opts.chunk_size, opts.line_terminator, d.quote)
+        })?;
+        CsvDocument::new(doc, chosen.unwrap_or_default())
+    }
+
+    /// Parse un fichier CSV/TSV via mmap, avec validation UTF-8 optionnelle.
+    ///
+    /// Sans validation, lire les lignes via [`Document::get_line_safe`].
     pub fn parse_with_validation(path: &Path, validate_utf8: bool) -> Result<Document, ParseError> {
-        let file = File::open(path)?;
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let arc_map = Arc::new(mmap);
-        let data = &arc_map[..];
-        #[cfg(unix)]
-        unsafe {
-            libc::posix_madvise(
-                data.as_ptr() as *mut _,
-                data.len(),
-                libc::POSIX_MADV_SEQUENTIAL,
-            );
-        }
-        let offsets = Self::compute_offsets(data, 1);
-        Self::validate(data, &offsets, validate_utf8)?;
-        Ok(Document {
-            data: DocumentData::Mmap(arc_map),
-            offsets,
-        })
+        Self::parse_with(path, &Self::validation_options(validate_utf8))
     }
 
-    /// Parse un buffer mémoire, avec validation optionnelle.
+    /// Parse un buffer mémoire, avec validation UTF-8 optionnelle.
     pub fn parse_buffer(data: &[u8], validate_utf8: bool) -> Result<Document, ParseError> {
-        let offsets = Self::compute_offsets(data, 1);
-        Self::validate(data, &offsets, validate_utf8)?;
-        Ok(Document {
-            data: DocumentData::Buffer(Arc::new(data.to_vec())),
-            offsets,
-        })
+        Self::parse_bytes_with(data.to_vec(), &Self::validation_options(validate_utf8))
     }
 
-    /// Parse en n'indexant qu'une ligne sur `stride` (index partiel).
+    /// Lecture ligne à ligne d'un flux (stdin, pipe…) en mémoire bornée.
+    pub fn stream_reader<R: Read>(reader: R) -> LineStream<BufReader<R>> {
+        LineStream::from_reader(reader)
+    }
+
+    /// Parse en n'indexant qu'un enregistrement sur `stride` (index partiel).
+    ///
+    /// Tous les enregistrements, y compris ceux dont un champ entre guillemets contient
+    /// une fin de ligne, restent accessibles via [`SparseDocument::get_line`], par scan
+    /// depuis l'enregistrement indexé précédent. Taille de chunk, pool, validation,
+    /// terminateur, encodage (BOM ignoré) et madvise viennent de `opts` ; le guillemet
+    /// est celui du dialecte détecté.
     pub fn parse_with_partial_index(
         path: &Path,
         stride: usize,
-        validate_utf8: bool,
-    ) -> Result<Document, ParseError> {
-        let file = File::open(path)?;
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let arc_map = Arc::new(mmap);
-        let data = &arc_map[..];
-        #[cfg(unix)]
-        unsafe {
-            libc::posix_madvise(
-                data.as_ptr() as *mut _,
-                data.len(),
-                libc::POSIX_MADV_SEQUENTIAL,
-            );
-        }
-        let offsets = Self::compute_offsets(data, stride);
-        Self::validate(data, &offsets, validate_utf8)?;
-        Ok(Document {
-            data: DocumentData::Mmap(arc_map),
-            offsets,
-        })
+        opts: &ParseOptions,
+    ) -> Result<SparseDocument, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        let strict = opts.validation == Validation::Strict;
+        let (data, records, quote) = opts
+            .install(|| {
+                let (data, bom) = encoding::to_utf8(
+                    backing,
+                    opts.encoding,
+                    opts.detect_encoding,
+                    opts.chunk_size,
+                    strict,
+                )?;
+                // Guillemet du dialecte détecté, comme pour `parse_csv`.
+                let quote = Dialect::sniff(&data.as_bytes()[bom..]).quote;
+                let records = records::index_records(
+                    data.as_bytes(),
+                    bom,
+                    opts.chunk_size,
+                    opts.line_terminator,
+                    quote,
+                );
+                Ok((data, records, quote))
+            })
+            .map_err(|e: ParseError| e.with_path(path))?;
+        SparseDocument::from_records(data, &records, stride, quote, opts)
+            .map_err(|e| e.with_path(path))
     }
 }
 
 impl DocumentParser for CsvParser {
-    fn parse(path: &Path) -> Result<Document, ParseError> {
-        Self::parse_with_validation(path, false)
+    const INDEX_KIND: u8 = b'c';
+
+    fn index_tag(opts: &ParseOptions) -> IndexTag {
+        IndexTag::new(Self::INDEX_KIND, opts).quote(QUOTE)
+    }
+
+    fn parse_with(path: &Path, opts: &ParseOptions) -> Result<Document, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        Self::build(backing, opts)
+            .map(|doc| doc.with_path(path))
+            .map_err(|e| e.with_path(path))
+    }
+
+    fn parse_bytes_with(data: Vec<u8>, opts: &ParseOptions) -> Result<Document, ParseError> {
+        Self::build(DocumentData::Buffer(Arc::new(data)), opts)
     }
 }
@@ -0,0 +1,156 @@
+//! Projection : lecture de quelques colonnes seulement d'un CSV large.
+//!
+//! Les champs sont délimités par [`Fields::next_raw`], qui suit les guillemets sans rien
+//! allouer ; les champs non demandés sont sautés et le découpage s'arrête après la
+//! dernière colonne utile. Seules les colonnes retenues sont décodées (guillemets et
+//! `""`) dans [`Projection::row`]. Le parcours parallèle reprend l'index des
+//! enregistrements construit à l'ouverture, qui tient compte des guillemets.
+
+use crate::{CsvDocument, Fields};
+use parser_core::ParseError;
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// Colonne demandée, par position (base 0) ou par nom d'en-tête.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnRef<'s> {
+    Index(usize),
+    Name(&'s str),
+}
+
+impl From<usize> for ColumnRef<'_> {
+    fn from(j: usize) -> Self {
+        ColumnRef::Index(j)
+    }
+}
+
+impl<'s> From<&'s str> for ColumnRef<'s> {
+    fn from(name: &'s str) -> Self {
+        ColumnRef::Name(name)
+    }
+}
+
+/// Vue projetée d'un [`CsvDocument`] sur une sélection de colonnes.
+pub struct Projection<'a> {
+    csv: &'a CsvDocument,
+    /// Colonnes lues, triées et sans doublon.
+    wanted: Vec<usize>,
+    /// Pour chaque colonne demandée, sa position dans `wanted`.
+    order: Vec<usize>,
+    names: Vec<String>,
+}
+
+impl CsvDocument {
+    /// Projette le document sur `columns`, dans l'ordre demandé (doublons permis).
+    pub fn project<'s, C: Into<ColumnRef<'s>>>(
+        &self,
+        columns: impl IntoIterator<Item = C>,
+    ) -> Result<Projection<'_>, ParseError> {
+        let columns = columns
+            .into_iter()
+            .map(|c| match c.into() {
+                ColumnRef::Index(j) => Ok(j),
+                ColumnRef::Name(name) => self
+                    .column_index(name)
+                    .ok_or_else(|| ParseError::Format(format!("unknown column: {name}"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut wanted = columns.clone();
+        wanted.sort_unstable();
+        wanted.dedup();
+        let order = columns
+            .iter()
+            .map(|j| wanted.binary_search(j).unwrap())
+            .collect();
+        let names = columns
+            .iter()
+            .map(|&j| {
+                self.headers()
+                    .get(j)
+                    .cloned()
+                    .unwrap_or_else(|| format!("column_{}", j + 1))
+            })
+            .collect();
+        Ok(Projection {
+            csv: self,
+            wanted,
+            order,
+            names,
+        })
+    }
+}
+
+impl<'a> Projection<'a> {
+    /// Noms des colonnes projetées, dans l'ordre demandé.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Nombre d'enregistrements de données.
+    pub fn len(&self) -> usize {
+        self.csv.record_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Colonnes projetées de l'enregistrement de données `i`.
+    ///
+    /// Un enregistrement trop court pour une colonne demandée est une erreur.
+    pub fn row(&self, i: usize) -> Result<Vec<Cow<'a, str>>, ParseError> {
+        let dialect = self.csv.dialect();
+        Ok(self
+            .raw_row(i)?
+            .into_iter()
+            .map(|raw| {
+                Fields::new(raw, dialect.delimiter, dialect.quote)
+                    .next()
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Colonnes projetées telles qu'écrites dans le fichier (guillemets et `""` compris),
+    /// à recopier sans réécriture avec [`CsvWriter`](crate::CsvWriter).
+    pub fn raw_row(&self, i: usize) -> Result<Vec<&'a str>, ParseError> {
+        let record = self.csv.record(i)?;
+        let mut fields = record.fields();
+        let mut found = Vec::with_capacity(self.wanted.len());
+        let mut j = 0;
+        for &col in &self.wanted {
+            while j < col && fields.next_raw().is_some() {
+                j += 1;
+            }
+            match fields.next_raw() {
+                Some(raw) => found.push(raw),
+                None => {
+                    return Err(self.csv.record_error(
+                        i,
+                        record.as_str().len(),
+                        format!("missing column {} (record has {j} fields)", col + 1),
+                    ))
+                }
+            }
+            j += 1;
+        }
+        Ok(self.order.iter().map(|&k| found[k]).collect())
+    }
+
+    /// Document projeté.
+    pub fn document(&self) -> &'a CsvDocument {
+        self.csv
+    }
+
+    /// Itérateur sur les enregistrements projetés.
+    pub fn rows(&self) -> impl Iterator<Item = Result<Vec<Cow<'a, str>>, ParseError>> + '_ {
+        (0..self.len()).map(move |i| self.row(i))
+    }
+
+    /// Variante parallèle (rayon) de [`rows`](Self::rows), ordre conservé.
+    pub fn par_rows(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = Result<Vec<Cow<'a, str>>, ParseError>> + '_ {
+        (0..self.len()).into_par_iter().map(move |i| self.row(i))
+    }
+}
@@ -0,0 +1,104 @@
+//! Indexation des enregistrements CSV (RFC 4180), guillemets compris.
+//!
+//! Un terminateur entre guillemets ne termine pas l'enregistrement. Le scan reste
+//! parallèle : chaque chunk est parcouru une seule fois sous les deux hypothèses
+//! d'état initial (hors / dans des guillemets), puis une passe séquentielle sur les
+//! chunks retient l'hypothèse correcte de proche en proche. Les guillemets doublés
+//! (`""`) inversent deux fois l'état et sont donc neutres.
+
+use memchr::memchr2_iter;
+use parser_core::options::LineTerminator;
+use parser_core::LineIndex;
+use rayon::prelude::*;
+
+/// Résultat du scan d'un chunk, indépendant de son état initial.
+struct ChunkScan {
+    /// Positions des terminateurs hors guillemets si le chunk commence hors (`[0]`)
+    /// ou dans (`[1]`) des guillemets.
+    ends: [Vec<usize>; 2],
+    /// Vrai si le chunk contient un nombre impair de guillemets.
+    flips: bool,
+}
+
+fn scan_chunk(data: &[u8], start: usize, end: usize, term: u8, quote: u8) -> ChunkScan {
+    let slice = &data[start..end];
+    let mut inside = false;
+    let mut ends = [Vec::new(), Vec::new()];
+    for pos in memchr2_iter(term, quote, slice) {
+        if slice[pos] == quote {
+            inside = !inside;
+        } else {
+            // Hors guillemets pour l'hypothèse « début hors » si la parité est paire.
+            ends[inside as usize].push(start + pos);
+        }
+    }
+    ChunkScan {
+        ends,
+        flips: inside,
+    }
+}
+
+/// Indexe les enregistrements de `data` (à partir de l'octet `from`) en parallèle.
+///
+/// Le résultat est identique à un scan séquentiel, quelle que soit `chunk_size`.
+/// Un guillemet non refermé prolonge le dernier enregistrement jusqu'à la fin.
+pub fn index_records(
+    data: &[u8],
+    from: usize,
+    chunk_size: usize,
+    terminator: LineTerminator,
+    quote: u8,
+) -> LineIndex {
+    let len = data.len();
+    let term = terminator.byte();
+    let strip_cr = terminator.strips_cr();
+    let chunk_size = chunk_size.max(1);
+    let bounds: Vec<(usize, usize)> = (from..len)
+        .step_by(chunk_size)
+        .map(|s| (s, s.saturating_add(chunk_size).min(len)))
+        .collect();
+    let scans: Vec<ChunkScan> = bounds
+        .par_iter()
+        .map(|&(s, e)| scan_chunk(data, s, e, term, quote))
+        .collect();
+
+    // Passe séquentielle sur les chunks : état initial et début du premier enregistrement.
+    let mut plan = Vec::with_capacity(scans.len());
+    let mut inside = false;
+    let mut record_start = from;
+    for scan in &scans {
+        plan.push((inside as usize, record_start));
+        if let Some(&last) = scan.ends[inside as usize].last() {
+            record_start = last + 1;
+        }
+        inside ^= scan.flips;
+    }
+
+    let sets: Vec<LineIndex> = scans
+        .par_iter()
+        .zip(plan)
+        .map(|(scan, (state, mut start))| {
+            let ends = &scan.ends[state];
+            let mut local = LineIndex::with_capacity(len as u64, ends.len());
+            for &end in ends {
+                let stop = if strip_cr && end > start && data[end - 1] == b'\r' {
+                    end - 1
+                } else {
+                    end
+                };
+                local.push(start as u64, (stop - start) as u64);
+                start = end + 1;
+            }
+            local
+        })
+        .collect();
+
+    let mut offsets = LineIndex::with_capacity(len as u64, sets.iter().map(LineIndex::len).sum());
+    for set in sets {
+        offsets.append(set);
+    }
+    if record_start < len {
+        offsets.push(record_start as u64, (len - record_start) as u64);
+    }
+    offsets
+}
@@ -0,0 +1,181 @@
+//! Schéma d'un CSV : noms de colonnes et types inférés.
+//!
+//! L'inférence est parallèle (rayon) sur un échantillon ou sur tout le fichier :
+//! chaque thread accumule les types vus par colonne, puis les états sont fusionnés.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Type inféré d'une colonne.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    /// Date ISO 8601 (`AAAA-MM-JJ`), avec heure optionnelle.
+    Date,
+    String,
+}
+
+impl ColumnType {
+    /// Type d'une valeur, `None` pour une valeur nulle (vide, `null`, `NA`).
+    ///
+    /// `NaN`, `inf`, `Infinity` (toute casse) et les flottants hors limites sont des chaînes.
+    pub fn of(value: &str) -> Option<Self> {
+        let v = value.trim();
+        if is_null(v) {
+            None
+        } else if v.parse::<i64>().is_ok() {
+            Some(ColumnType::Int)
+        } else if v.parse::<f64>().is_ok_and(f64::is_finite) {
+            Some(ColumnType::Float)
+        } else if v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false") {
+            Some(ColumnType::Bool)
+        } else if is_date(v) {
+            Some(ColumnType::Date)
+        } else {
+            Some(ColumnType::String)
+        }
+    }
+
+    /// Type commun à deux types : `Int` + `Float` donne `Float`, tout autre mélange `String`.
+    pub fn merge(self, other: Self) -> Self {
+        use ColumnType::*;
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Int, Float) | (Float, Int) => Float,
+            _ => String,
+        }
+    }
+
+    /// Vrai pour `Int` et `Float`.
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Int | ColumnType::Float)
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::Bool => "bool",
+            ColumnType::Date => "date",
+            ColumnType::String => "string",
+        })
+    }
+}
+
+fn is_null(v: &str) -> bool {
+    v.is_empty() || v.eq_ignore_ascii_case("null") || v == "NA" || v.eq_ignore_ascii_case("n/a")
+}
+
+/// `AAAA-MM-JJ`, suivi éventuellement de `T` ou d'une espace et `HH:MM[:SS…]`.
+fn is_date(v: &str) -> bool {
+    let b = v.as_bytes();
+    let digits = |r: std::ops::Range<usize>| b[r].iter().all(u8::is_ascii_digit);
+    if b.len() < 10 || !digits(0..4) || b[4] != b'-' || !digits(5..7) || b[7] != b'-' {
+        return false;
+    }
+    if !digits(8..10) {
+        return false;
+    }
+    let month = (b[5] - b'0') * 10 + (b[6] - b'0');
+    let day = (b[8] - b'0') * 10 + (b[9] - b'0');
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return false;
+    }
+    match &b[10..] {
+        [] => true,
+        [b'T' | b' ', h1, h2, b':', m1, m2, ..] => {
+            [h1, h2, m1, m2].iter().all(|c| c.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+/// Colonne d'un schéma.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub name: String,
+    /// Type des valeurs non nulles (`String` si la colonne n'a que des nulls).
+    pub ty: ColumnType,
+    /// Vrai si au moins une valeur est nulle ou absente.
+    pub nullable: bool,
+}
+
+/// Schéma d'un document CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub columns: Vec<Column>,
+}
+
+impl Schema {
+    /// Colonne nommée `name`.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
+
+/// Accumulateur d'inférence pour une colonne.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ColumnState {
+    ty: Option<ColumnType>,
+    nullable: bool,
+}
+
+impl ColumnState {
+    pub(crate) fn observe(&mut self, value: Option<&str>) {
+        match value.and_then(ColumnType::of) {
+            Some(t) => self.ty = Some(self.ty.map_or(t, |cur| cur.merge(t))),
+            None => self.nullable = true,
+        }
+    }
+
+    pub(crate) fn merge(self, other: Self) -> Self {
+        ColumnState {
+            ty: match (self.ty, other.ty) {
+                (Some(a), Some(b)) => Some(a.merge(b)),
+                (a, b) => a.or(b),
+            },
+            nullable: self.nullable || other.nullable,
+        }
+    }
+
+    pub(crate) fn into_column(self, name: String) -> Column {
+        Column {
+            name,
+            ty: self.ty.unwrap_or(ColumnType::String),
+            nullable: self.nullable,
+        }
+    }
+}
+
+/// Noms de colonnes uniques : vides remplacés par `column_<n>`, doublons suffixés `_2`, `_3`…
+pub fn dedup_names<I: IntoIterator<Item = String>>(names: I) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for (j, name) in names.into_iter().enumerate() {
+        let base = match name.trim() {
+            "" => format!("column_{}", j + 1),
+            n => n.to_string(),
+        };
+        let mut candidate = base.clone();
+        let mut k = 2;
+        while !seen.insert(candidate.clone()) {
+            candidate = format!("{base}_{k}");
+            k += 1;
+        }
+        out.push(candidate);
+    }
+    out
+}
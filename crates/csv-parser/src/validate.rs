@@ -0,0 +1,240 @@
+//! Validation structurelle d'un CSV : lignes irrégulières et guillemets mal formés.
+//!
+//! Chaque enregistrement est vérifié en parallèle (rayon) sur ses octets bruts ; le
+//! rapport agrège les compteurs par type d'anomalie et garde les N premiers exemples.
+
+use crate::CsvDocument;
+use rayon::prelude::*;
+use std::fmt;
+
+/// Anomalie détectée dans un enregistrement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Nombre de champs différent de celui de l'en-tête (ou du premier enregistrement).
+    FieldCount { expected: usize, found: usize },
+    /// Guillemet ouvrant jamais refermé : l'enregistrement court jusqu'à la fin du fichier.
+    UnterminatedQuote,
+    /// Guillemet dans un champ non cité, ou texte collé après un guillemet fermant.
+    StrayQuote,
+    /// Séparateur en fin d'enregistrement qui ajoute un champ vide en trop.
+    TrailingDelimiter,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::FieldCount { expected, found } => {
+                write!(f, "expected {expected} fields, found {found}")
+            }
+            IssueKind::UnterminatedQuote => f.write_str("unterminated quoted field"),
+            IssueKind::StrayQuote => f.write_str("stray quote in unquoted field"),
+            IssueKind::TrailingDelimiter => f.write_str("trailing delimiter"),
+        }
+    }
+}
+
+/// Exemple d'anomalie localisée.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// Index de l'enregistrement de données (base 0, en-tête exclu).
+    pub record: usize,
+    /// Ligne physique du fichier (base 1), fins de ligne entre guillemets comprises.
+    pub line: usize,
+    /// Offset absolu de l'anomalie en octets.
+    pub byte_offset: u64,
+}
+
+/// Rapport de validation structurelle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Enregistrements de données examinés.
+    pub records: usize,
+    /// Nombre de champs attendu par enregistrement.
+    pub expected_fields: usize,
+    pub field_count_mismatches: usize,
+    pub unterminated_quotes: usize,
+    pub stray_quotes: usize,
+    pub trailing_delimiters: usize,
+    /// Premières anomalies, dans l'ordre du fichier.
+    pub examples: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Nombre total d'anomalies.
+    pub fn issue_count(&self) -> usize {
+        self.field_count_mismatches
+            + self.unterminated_quotes
+            + self.stray_quotes
+            + self.trailing_delimiters
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.issue_count() == 0
+    }
+
+    fn count(&mut self, kind: IssueKind) {
+        match kind {
+            IssueKind::FieldCount { .. } => self.field_count_mismatches += 1,
+            IssueKind::UnterminatedQuote => self.unterminated_quotes += 1,
+            IssueKind::StrayQuote => self.stray_quotes += 1,
+            IssueKind::TrailingDelimiter => self.trailing_delimiters += 1,
+        }
+    }
+
+    /// Fusionne un rapport portant sur des enregistrements postérieurs.
+    fn merge(mut self, other: Self, max_examples: usize) -> Self {
+        self.records += other.records;
+        self.field_count_mismatches += other.field_count_mismatches;
+        self.unterminated_quotes += other.unterminated_quotes;
+        self.stray_quotes += other.stray_quotes;
+        self.trailing_delimiters += other.trailing_delimiters;
+        let room = max_examples.saturating_sub(self.examples.len());
+        self.examples.extend(other.examples.into_iter().take(room));
+        self
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "records: {}, expected fields: {}",
+            self.records, self.expected_fields
+        )?;
+        writeln!(f, "field count mismatches: {}", self.field_count_mismatches)?;
+        writeln!(f, "unterminated quotes: {}", self.unterminated_quotes)?;
+        writeln!(f, "stray quotes: {}", self.stray_quotes)?;
+        write!(f, "trailing delimiters: {}", self.trailing_delimiters)?;
+        for issue in &self.examples {
+            write!(
+                f,
+                "\nline {} (record {}, byte {}): {}",
+                issue.line, issue.record, issue.byte_offset, issue.kind
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Structure d'un enregistrement brut.
+struct Shape {
+    fields: usize,
+    unterminated: bool,
+    /// Position du premier guillemet parasite.
+    stray_quote: Option<usize>,
+    /// Vrai si l'enregistrement se termine par un séparateur hors guillemets.
+    trailing_delimiter: bool,
+}
+
+fn shape(raw: &[u8], delimiter: u8, quote: u8) -> Shape {
+    let len = raw.len();
+    let mut shape = Shape {
+        fields: 1,
+        unterminated: false,
+        stray_quote: None,
+        trailing_delimiter: false,
+    };
+    let mut i = 0;
+    loop {
+        let end = if raw.get(i) == Some(&quote) {
+            let mut j = i + 1;
+            let close = loop {
+                match memchr::memchr(quote, &raw[j..]) {
+                    Some(p) if raw.get(j + p + 1) == Some(&quote) => j += p + 2,
+                    Some(p) => break Some(j + p),
+                    None => break None,
+                }
+            };
+            let Some(close) = close else {
+                shape.unterminated = true;
+                return shape;
+            };
+            let after = close + 1;
+            let end = memchr::memchr(delimiter, &raw[after..]).map_or(len, |p| after + p);
+            if end > after {
+                shape.stray_quote = shape.stray_quote.or(Some(after));
+            }
+            end
+        } else {
+            let end = memchr::memchr(delimiter, &raw[i..]).map_or(len, |p| i + p);
+            if let Some(q) = memchr::memchr(quote, &raw[i..end]) {
+                shape.stray_quote = shape.stray_quote.or(Some(i + q));
+            }
+            end
+        };
+        if end == len {
+            return shape;
+        }
+        shape.fields += 1;
+        i = end + 1;
+        shape.trailing_delimiter = i == len;
+    }
+}
+
+impl CsvDocument {
+    /// Vérifie la structure de chaque enregistrement de données, en parallèle.
+    ///
+    /// Le nombre de champs attendu est celui de la première ligne (en-tête ou non).
+    /// Le rapport garde au plus `max_examples` anomalies, les premières du fichier.
+    pub fn validate(&self, max_examples: usize) -> ValidationReport {
+        let doc = self.document();
+        let data = doc.data.as_bytes();
+        let dialect = self.dialect();
+        let expected = self.headers().len();
+        let first = self.header_lines();
+        let mut report = (first..doc.line_count())
+            .into_par_iter()
+            .with_min_len(1024)
+            .fold(ValidationReport::default, |mut report, line| {
+                report.records += 1;
+                let Some((start, len)) = doc.offsets.get(line) else {
+                    return report;
+                };
+                let raw = &data[start as usize..(start + len) as usize];
+                let s = shape(raw, dialect.delimiter, dialect.quote);
+                // Par position croissante dans l'enregistrement.
+                let mut issues = Vec::new();
+                if s.unterminated {
+                    issues.push((IssueKind::UnterminatedQuote, 0));
+                }
+                if s.fields != expected {
+                    let kind = if s.trailing_delimiter && s.fields == expected + 1 {
+                        IssueKind::TrailingDelimiter
+                    } else {
+                        IssueKind::FieldCount {
+                            expected,
+                            found: s.fields,
+                        }
+                    };
+                    issues.push((kind, 0));
+                }
+                if let Some(q) = s.stray_quote {
+                    issues.push((IssueKind::StrayQuote, q));
+                }
+                for (kind, offset) in issues {
+                    report.count(kind);
+                    if report.examples.len() < max_examples {
+                        report.examples.push(Issue {
+                            kind,
+                            record: line - first,
+                            line: 0,
+                            byte_offset: start + offset as u64,
+                        });
+                    }
+                }
+                report
+            })
+            .reduce(ValidationReport::default, |a, b| a.merge(b, max_examples));
+        report.expected_fields = expected;
+        // Lignes physiques : un seul parcours jusqu'au dernier exemple.
+        let (mut pos, mut line) = (0usize, 1usize);
+        for issue in &mut report.examples {
+            let offset = issue.byte_offset as usize;
+            line += memchr::memchr_iter(b'\n', &data[pos..offset]).count();
+            pos = offset;
+            issue.line = line;
+        }
+        report
+    }
+}
@@ -0,0 +1,478 @@
+//! Écriture CSV bufferisée, selon un [`Dialect`].
+//!
+//! - Un champ n'est cité que s'il contient le séparateur, le guillemet ou une fin de ligne
+//! - Enregistrements et colonnes projetées d'un document recopiés tels quels quand le
+//!   dialecte est le même (pas de déséchappement / réécriture)
+//! - Écriture depuis des structs serde, en-tête déduit des noms de champs
+
+use crate::{Dialect, Fields, Projection, Record};
+use parser_core::ParseError;
+use serde::ser::{self, Impossible, Serialize, Serializer};
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Enregistrement en cours de construction (déjà échappé).
+struct RecordBuf {
+    buf: Vec<u8>,
+    fields: usize,
+    delimiter: u8,
+    quote: u8,
+    /// Noms des champs de struct, collectés pour l'en-tête.
+    names: Option<Vec<&'static str>>,
+    scratch: String,
+}
+
+impl RecordBuf {
+    fn new(dialect: Dialect) -> Self {
+        RecordBuf {
+            buf: Vec::new(),
+            fields: 0,
+            delimiter: dialect.delimiter,
+            quote: dialect.quote,
+            names: None,
+            scratch: String::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.buf.clear();
+        self.fields = 0;
+    }
+
+    fn separate(&mut self) {
+        if self.fields > 0 {
+            self.buf.push(self.delimiter);
+        }
+        self.fields += 1;
+    }
+
+    fn push_field(&mut self, value: &str) {
+        self.separate();
+        let bytes = value.as_bytes();
+        let needs_quote = memchr::memchr3(self.delimiter, self.quote, b'\n', bytes).is_some()
+            || memchr::memchr(b'\r', bytes).is_some();
+        if !needs_quote {
+            self.buf.extend_from_slice(bytes);
+            return;
+        }
+        self.buf.push(self.quote);
+        let mut start = 0;
+        for p in memchr::memchr_iter(self.quote, bytes) {
+            self.buf.extend_from_slice(&bytes[start..=p]);
+            self.buf.push(self.quote);
+            start = p + 1;
+        }
+        self.buf.extend_from_slice(&bytes[start..]);
+        self.buf.push(self.quote);
+    }
+
+    /// Champ brut écrit avec le dialecte `source` : recopié si c'est le même dialecte.
+    fn push_raw(&mut self, raw: &str, source: (u8, u8)) {
+        if source == (self.delimiter, self.quote) {
+            self.separate();
+            self.buf.extend_from_slice(raw.as_bytes());
+        } else {
+            let value = Fields::new(raw, source.0, source.1)
+                .next()
+                .unwrap_or_default();
+            self.push_field(&value);
+        }
+    }
+
+    fn push_display(&mut self, value: impl fmt::Display) {
+        let mut s = std::mem::take(&mut self.scratch);
+        s.clear();
+        let _ = write!(s, "{value}");
+        self.push_field(&s);
+        self.scratch = s;
+    }
+}
+
+/// Écrivain CSV bufferisé.
+pub struct CsvWriter<W: Write> {
+    out: BufWriter<W>,
+    dialect: Dialect,
+    record: RecordBuf,
+    records: u64,
+}
+
+impl CsvWriter<File> {
+    /// Crée (ou tronque) le fichier `path`.
+    pub fn create(path: &Path, dialect: Dialect) -> Result<Self, ParseError> {
+        Ok(Self::new(File::create(path)?, dialect))
+    }
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Le séparateur et le guillemet du dialecte doivent être des octets ASCII.
+    pub fn new(inner: W, dialect: Dialect) -> Self {
+        CsvWriter {
+            out: BufWriter::with_capacity(1 << 16, inner),
+            dialect,
+            record: RecordBuf::new(dialect),
+            records: 0,
+        }
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Enregistrements écrits (en-tête compris).
+    pub fn records_written(&self) -> u64 {
+        self.records
+    }
+
+    fn end_record(&mut self) -> Result<(), ParseError> {
+        self.record.buf.push(b'\n');
+        self.out.write_all(&self.record.buf)?;
+        self.record.clear();
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Écrit un enregistrement, champs échappés si besoin.
+    pub fn write_record<I, S>(&mut self, fields: I) -> Result<(), ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for field in fields {
+            self.record.push_field(field.as_ref());
+        }
+        self.end_record()
+    }
+
+    /// Écrit un enregistrement lu dans un document : recopié octet pour octet si son
+    /// dialecte est celui de l'écrivain, réécrit champ par champ sinon.
+    pub fn write_raw_record(&mut self, record: &Record<'_>) -> Result<(), ParseError> {
+        if (record.delimiter, record.quote) == (self.dialect.delimiter, self.dialect.quote) {
+            self.record
+                .buf
+                .extend_from_slice(record.as_str().as_bytes());
+        } else {
+            for value in record.fields() {
+                self.record.push_field(&value);
+            }
+        }
+        self.end_record()
+    }
+
+    /// Écrit l'enregistrement de données `i` d'une projection, champs bruts recopiés.
+    pub fn write_projected(
+        &mut self,
+        projection: &Projection<'_>,
+        i: usize,
+    ) -> Result<(), ParseError> {
+        let dialect = projection.document().dialect();
+        for raw in projection.raw_row(i)? {
+            self.record
+                .push_raw(raw, (dialect.delimiter, dialect.quote));
+        }
+        self.end_record()
+    }
+
+    /// Écrit une valeur serde : struct (champs dans l'ordre de déclaration), tuple ou
+    /// séquence de scalaires. Avec en-tête, le premier struct écrit aussi les noms de champs.
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ParseError> {
+        let header = self.dialect.has_header && self.records == 0;
+        self.record.names = header.then(Vec::new);
+        let result = value.serialize(&mut self.record);
+        let names = self.record.names.take();
+        if let Err(e) = result {
+            self.record.clear();
+            return Err(ParseError::Format(e.0));
+        }
+        if let Some(names) = names.filter(|n| !n.is_empty()) {
+            let mut head = RecordBuf::new(self.dialect);
+            for name in names {
+                head.push_field(name);
+            }
+            head.buf.push(b'\n');
+            self.out.write_all(&head.buf)?;
+            self.records += 1;
+        }
+        self.end_record()
+    }
+
+    pub fn flush(&mut self) -> Result<(), ParseError> {
+        Ok(self.out.flush()?)
+    }
+
+    /// Vide le buffer et rend l'écrivain sous-jacent.
+    pub fn into_inner(self) -> Result<W, ParseError> {
+        self.out.into_inner().map_err(|e| e.into_error().into())
+    }
+}
+
+/// Erreur de sérialisation (type non représentable dans un champ CSV).
+#[derive(Debug)]
+pub(crate) struct SerError(String);
+
+impl ser::Error for SerError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerError(msg.to_string())
+    }
+}
+
+impl fmt::Display for SerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SerError {}
+
+fn unsupported(what: &str) -> SerError {
+    SerError(format!("cannot write {what} as a CSV field"))
+}
+
+/// Un scalaire produit un champ ; `None` et `()` un champ vide.
+macro_rules! serialize_scalars {
+    ($($method:ident $ty:ty,)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), SerError> {
+                self.target().push_display(v);
+                Ok(())
+            }
+        )*
+    };
+}
+
+macro_rules! field_serializer_body {
+    () => {
+        serialize_scalars! {
+            serialize_bool bool, serialize_i8 i8, serialize_i16 i16, serialize_i32 i32,
+            serialize_i64 i64, serialize_i128 i128, serialize_u8 u8, serialize_u16 u16,
+            serialize_u32 u32, serialize_u64 u64, serialize_u128 u128, serialize_f32 f32,
+            serialize_f64 f64, serialize_char char,
+        }
+
+        fn serialize_str(self, v: &str) -> Result<(), SerError> {
+            self.target().push_field(v);
+            Ok(())
+        }
+
+        fn serialize_bytes(self, v: &[u8]) -> Result<(), SerError> {
+            let v = std::str::from_utf8(v).map_err(|_| unsupported("non UTF-8 bytes"))?;
+            self.serialize_str(v)
+        }
+
+        fn serialize_none(self) -> Result<(), SerError> {
+            self.serialize_str("")
+        }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<(), SerError> {
+            self.serialize_str("")
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerError> {
+            self.serialize_str("")
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            variant: &'static str,
+        ) -> Result<(), SerError> {
+            self.serialize_str(variant)
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<(), SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<(), SerError> {
+            Err(unsupported("an enum variant with data"))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Impossible<(), SerError>, SerError> {
+            Err(unsupported("an enum variant with data"))
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<Impossible<(), SerError>, SerError> {
+            Err(unsupported("a map"))
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Impossible<(), SerError>, SerError> {
+            Err(unsupported("an enum variant with data"))
+        }
+    };
+}
+
+/// Sérialiseur d'un champ : scalaires seulement.
+struct FieldSerializer<'b>(&'b mut RecordBuf);
+
+/// Accès au buffer commun aux deux sérialiseurs.
+trait Target<'b> {
+    fn target(self) -> &'b mut RecordBuf;
+}
+
+impl<'b> Target<'b> for FieldSerializer<'b> {
+    fn target(self) -> &'b mut RecordBuf {
+        self.0
+    }
+}
+
+impl<'b> Target<'b> for &'b mut RecordBuf {
+    fn target(self) -> &'b mut RecordBuf {
+        self
+    }
+}
+
+impl Serializer for FieldSerializer<'_> {
+    type Ok = ();
+    type Error = SerError;
+    type SerializeSeq = Impossible<(), SerError>;
+    type SerializeTuple = Impossible<(), SerError>;
+    type SerializeTupleStruct = Impossible<(), SerError>;
+    type SerializeTupleVariant = Impossible<(), SerError>;
+    type SerializeMap = Impossible<(), SerError>;
+    type SerializeStruct = Impossible<(), SerError>;
+    type SerializeStructVariant = Impossible<(), SerError>;
+
+    field_serializer_body!();
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerError> {
+        Err(unsupported("a nested sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerError> {
+        Err(unsupported("a nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerError> {
+        Err(unsupported("a nested tuple"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, SerError> {
+        Err(unsupported("a nested struct"))
+    }
+}
+
+/// Sérialiseur d'un enregistrement : struct, tuple ou séquence, un champ par élément.
+impl Serializer for &mut RecordBuf {
+    type Ok = ();
+    type Error = SerError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Impossible<(), SerError>;
+    type SerializeMap = Impossible<(), SerError>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), SerError>;
+
+    field_serializer_body!();
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, SerError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, SerError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, SerError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, SerError> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut RecordBuf {
+    type Ok = ();
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerError> {
+        value.serialize(FieldSerializer(self))
+    }
+
+    fn end(self) -> Result<(), SerError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut RecordBuf {
+    type Ok = ();
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerError> {
+        value.serialize(FieldSerializer(self))
+    }
+
+    fn end(self) -> Result<(), SerError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut RecordBuf {
+    type Ok = ();
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerError> {
+        value.serialize(FieldSerializer(self))
+    }
+
+    fn end(self) -> Result<(), SerError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut RecordBuf {
+    type Ok = ();
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerError> {
+        if let Some(names) = &mut self.names {
+            names.push(key);
+        }
+        value.serialize(FieldSerializer(self))
+    }
+
+    fn end(self) -> Result<(), SerError> {
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use csv_parser::CsvParser;
-use parser_core::DocumentParser;
-use std::io::Write;
+use parser_core::encoding::Encoding;
+use parser_core::{DocumentParser, ParseError, ParseOptions};
+use proptest::prelude::*;
+use std::io::{Seek, SeekFrom, Write};
 use tempfile::NamedTempFile;
 
 #[test]
@@ -52,6 +54,17 @@ fn test_parse_csv_malformed_utf8() {
     file.write_all(bad_bytes).unwrap();
     let res = CsvParser::parse(file.path());
     assert!(res.is_err());
+    assert!(CsvParser::parse_with_validation(file.path(), true).is_err());
+
+    // Sans validation : parsing accepté, lecture sûre via get_line_safe.
+    let doc = CsvParser::parse_with_validation(file.path(), false).unwrap();
+    assert_eq!(doc.line_count(), 3);
+    assert_eq!(doc.get_line_safe(1).unwrap(), "1,2,");
+    let err = doc.get_line_safe(2).unwrap_err();
+    let location = err.location().unwrap();
+    assert_eq!(location.line, Some(2));
+    assert_eq!(location.byte_offset, Some(11));
+    assert_eq!(location.column, Some(1));
 }
 
 #[test]
@@ -60,3 +73,727 @@ fn test_parse_csv_file_not_found() {
     let res = CsvParser::parse(path);
     assert!(res.is_err());
 }
+
+#[test]
+fn test_parse_csv_offsets_beyond_4gib() {
+    // Fichier creux : dernières lignes au-delà de 4 GiB, ligne de zéros à cheval sur les chunks.
+    let mut file = NamedTempFile::new().unwrap();
+    let far = (1u64 << 32) + 4096;
+    file.write_all(b"a,b,c\n").unwrap();
+    file.seek(SeekFrom::Start(far - 1)).unwrap();
+    file.write_all(b"\n1,2,3\r\n4,5,6\n").unwrap();
+    file.flush().unwrap();
+
+    let doc = CsvParser::parse(file.path()).unwrap();
+    assert!(doc.offsets.is_wide());
+    assert_eq!(doc.line_count(), 4);
+    assert_eq!(doc.get_line(0).unwrap(), "a,b,c");
+    assert_eq!(doc.offsets.get(2), Some((far, 5)));
+    assert_eq!(doc.offsets.get(3), Some((far + 7, 5)));
+    assert_eq!(doc.lines_range(2, 4).unwrap(), vec!["1,2,3", "4,5,6"]);
+}
+
+proptest! {
+    #[test]
+    fn prop_compute_offsets_independent_of_chunk_size(
+        data in proptest::collection::vec(
+            prop_oneof![Just(b'\n'), Just(b'\r'), Just(b','), Just(b'"'), any::<u8>()],
+            0..512,
+        ),
+        chunk_size in 1usize..64,
+    ) {
+        let chunked = CsvParser::compute_offsets_chunked(&data, chunk_size);
+        prop_assert_eq!(chunked.iter().collect::<Vec<_>>(), sequential_records(&data));
+    }
+}
+
+/// Découpage séquentiel de référence : `\n` hors guillemets, `\r` final retiré.
+fn sequential_records(data: &[u8]) -> Vec<(u64, u64)> {
+    let mut records = Vec::new();
+    let (mut start, mut inside) = (0, false);
+    for (i, &b) in data.iter().enumerate() {
+        match b {
+            b'"' => inside = !inside,
+            b'\n' if !inside => {
+                let end = if i > start && data[i - 1] == b'\r' {
+                    i - 1
+                } else {
+                    i
+                };
+                records.push((start as u64, (end - start) as u64));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < data.len() {
+        records.push((start as u64, (data.len() - start) as u64));
+    }
+    records
+}
+
+#[test]
+fn test_parse_csv_quoted_fields() {
+    let data = b"id,comment\r\n1,\"multi\r\nline\"\r\n2,\"say \"\"hi\"\"\nagain\"\r\n3,plain\r\n";
+    let doc = CsvParser::parse_bytes(data.to_vec()).unwrap();
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec![
+            "id,comment",
+            "1,\"multi\r\nline\"",
+            "2,\"say \"\"hi\"\"\nagain\"",
+            "3,plain"
+        ]
+    );
+    // Même résultat quel que soit le découpage en chunks.
+    for chunk in 1..data.len() {
+        assert_eq!(
+            CsvParser::compute_offsets_chunked(data, chunk),
+            doc.offsets,
+            "chunk {chunk}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_csv_unterminated_quote() {
+    let doc = CsvParser::parse_bytes(b"a,b\n1,\"open\n2,3\n".to_vec()).unwrap();
+    assert_eq!(doc.line_count(), 2);
+    assert_eq!(doc.get_line(1).unwrap(), "1,\"open\n2,3\n");
+}
+
+#[test]
+fn test_parse_with_partial_index_random_access() {
+    let mut file = NamedTempFile::new().unwrap();
+    for i in 0..7 {
+        writeln!(file, "{i},{}", i * 10).unwrap();
+    }
+    let doc =
+        CsvParser::parse_with_partial_index(file.path(), 3, &ParseOptions::default()).unwrap();
+    assert_eq!(doc.line_count(), 7);
+    assert_eq!(doc.checkpoint_count(), 3);
+    // Les lignes entre deux points de reprise ne sont plus perdues.
+    assert_eq!(doc.get_line(1).unwrap(), "1,10");
+    assert_eq!(doc.get_line(4).unwrap(), "4,40");
+    assert_eq!(doc.get_line(6).unwrap(), "6,60");
+    assert!(doc.get_line(7).is_err());
+}
+
+#[test]
+fn test_parse_with_partial_index_quoted_newline() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"a,b\n\"x\ny\",2\r\nz,3\n").unwrap();
+    let opts = ParseOptions::new().chunk_size(4);
+    let doc = CsvParser::parse_with_partial_index(file.path(), 2, &opts).unwrap();
+    assert_eq!(doc.line_count(), 3);
+    assert_eq!(doc.checkpoint_count(), 2);
+    assert_eq!(doc.get_line(1).unwrap(), "\"x\ny\",2");
+    assert_eq!(doc.get_line(2).unwrap(), "z,3");
+    assert_eq!(
+        doc.lines().map(Result::unwrap).collect::<Vec<_>>(),
+        vec!["a,b", "\"x\ny\",2", "z,3"]
+    );
+}
+
+#[test]
+fn test_blank_record_has_one_empty_field() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"a,b\n1,2\n\n3,4\n").unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    let blank = csv.record(1).unwrap();
+    assert!(blank.is_blank());
+    assert_eq!(blank.len(), 1);
+    assert!(!blank.is_empty());
+    assert!(!csv.record(0).unwrap().is_blank());
+}
+
+#[test]
+fn test_parse_with_partial_index_sniffs_quote() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"nom;note\n'a\nb';1\n'c';2\n").unwrap();
+    let doc = CsvParser::parse_with_partial_index(file.path(), 2, &ParseOptions::new()).unwrap();
+    assert_eq!(doc.line_count(), 3);
+    assert_eq!(doc.get_line(1).unwrap(), "'a\nb';1");
+    assert_eq!(doc.get_line(2).unwrap(), "'c';2");
+}
+
+#[test]
+fn test_parse_with_partial_index_honors_encoding() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"\xEF\xBB\xBFa,b\n1,2\n").unwrap();
+    let doc = CsvParser::parse_with_partial_index(file.path(), 1, &ParseOptions::new()).unwrap();
+    assert_eq!(doc.get_line(0).unwrap(), "a,b");
+    assert_eq!(
+        doc.lines().map(Result::unwrap).collect::<Vec<_>>(),
+        vec!["a,b", "1,2"]
+    );
+
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"nom\n\"caf\xE9\"\n").unwrap();
+    let opts = ParseOptions::new().encoding(Encoding::Latin1);
+    let doc = CsvParser::parse_with_partial_index(file.path(), 1, &opts).unwrap();
+    assert_eq!(doc.get_line(1).unwrap(), "\"café\"");
+}
+
+#[test]
+fn test_parse_csv_from_reader() {
+    let input: &[u8] = b"a,b\r\n1,2\n";
+    let doc = CsvParser::parse_reader(input).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["a,b", "1,2"]);
+    let streamed: Vec<String> = CsvParser::stream_reader(input)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(streamed, vec!["a,b", "1,2"]);
+}
+
+fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+    let mut out = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+    out.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    out
+}
+
+#[test]
+fn test_parse_non_utf8_encodings() {
+    use parser_core::encoding::Encoding;
+
+    // Windows-1252 explicite : é = 0xE9, € = 0x80
+    let cp1252 = b"nom;prix\ncaf\xE9;3\x80\n".to_vec();
+    assert!(CsvParser::parse_bytes(cp1252.clone()).is_err());
+    let opts = ParseOptions::new().encoding(Encoding::Windows1252);
+    let doc = CsvParser::parse_bytes_with(cp1252.clone(), &opts).unwrap();
+    assert_eq!(doc.get_line(1).unwrap(), "café;3€");
+    let latin1 = ParseOptions::new().encoding(Encoding::Latin1);
+    let doc = CsvParser::parse_bytes_with(cp1252.clone(), &latin1).unwrap();
+    assert_eq!(doc.get_line(1).unwrap(), "café;3\u{80}");
+    let detected = ParseOptions::new().detect_encoding(true);
+    let doc = CsvParser::parse_bytes_with(cp1252, &detected).unwrap();
+    assert_eq!(doc.get_line(1).unwrap(), "café;3€");
+
+    // UTF-16LE : BOM reconnu par défaut, heuristique sans BOM
+    let text = "a,b\r\n1,ü\r\n2,😀\r\n";
+    let doc = CsvParser::parse_bytes(utf16le(text, true)).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["a,b", "1,ü", "2,😀"]);
+    let doc = CsvParser::parse_bytes_with(utf16le(text, false), &detected).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["a,b", "1,ü", "2,😀"]);
+
+    // BOM UTF-8 retiré de la première ligne
+    let doc = CsvParser::parse_bytes(b"\xEF\xBB\xBFa,b\n1,2\n".to_vec()).unwrap();
+    assert_eq!(doc.get_line(0).unwrap(), "a,b");
+}
+
+#[test]
+fn test_invalid_encoded_data_is_located() {
+    // Surrogate isolé (0xDC00) en deuxième ligne, après le BOM et « 1, ».
+    let mut data = utf16le("a,b\r\n1,", true);
+    data.extend_from_slice(&[0x00, 0xDC]);
+    data.extend(utf16le("\r\n", false));
+    let err = CsvParser::parse_bytes(data).err().unwrap();
+    assert!(matches!(err, ParseError::InvalidLine { .. }));
+    let location = err.location().unwrap();
+    assert_eq!(location.line, Some(1));
+    assert_eq!(location.byte_offset, Some(16));
+    assert_eq!(location.column, Some(5));
+    assert!(err.to_string().contains("UTF-16LE"));
+    assert_eq!(location.snippet.as_deref(), Some("1,\u{FFFD}"));
+}
+
+proptest! {
+    #[test]
+    fn prop_utf16_transcoding_chunk_independent(
+        rows in proptest::collection::vec("[a-z0-9éü😀,]{0,12}", 0..40),
+        chunk in 1usize..64,
+    ) {
+        use parser_core::ParseOptions;
+        let text: String = rows.iter().map(|r| format!("{r}\n")).collect();
+        let opts = ParseOptions::new().chunk_size(chunk);
+        let doc = CsvParser::parse_bytes_with(utf16le(&text, true), &opts).unwrap();
+        prop_assert_eq!(doc.lines().collect::<Vec<_>>(), rows.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_csv_document_field_access() {
+    use std::borrow::Cow;
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "id,name,comment\n1,alice,\"hello, world\"\n2,bob,\"say \"\"hi\"\"\nbye\"\n3,,plain\n"
+    )
+    .unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    assert_eq!(csv.headers(), &["id", "name", "comment"]);
+    assert_eq!(csv.record_count(), 3);
+    assert_eq!(csv.column_index("comment"), Some(2));
+
+    assert_eq!(csv.field(0, 1).unwrap(), "alice");
+    // Champ entre guillemets sans `""` : emprunté au mmap
+    assert!(matches!(
+        csv.field(0, 2).unwrap(),
+        Cow::Borrowed("hello, world")
+    ));
+    // `""` déséchappé : alloué
+    assert!(matches!(csv.field(1, 2).unwrap(), Cow::Owned(s) if s == "say \"hi\"\nbye"));
+    assert_eq!(csv.field_by_name(2, "name").unwrap(), "");
+    assert_eq!(
+        csv.fields(2).unwrap().collect::<Vec<_>>(),
+        vec!["3", "", "plain"]
+    );
+    assert_eq!(csv.record(1).unwrap().len(), 3);
+    assert!(csv.field(0, 3).is_err());
+    assert!(csv.record(3).is_err());
+    assert!(csv.field_by_name(0, "missing").is_err());
+    assert_eq!(csv.records().count(), 3);
+}
+
+#[test]
+fn test_fields_edge_cases() {
+    use csv_parser::Fields;
+    let split = |s| Fields::new(s, b',', b'"').collect::<Vec<_>>();
+    assert_eq!(split(""), vec![""]);
+    assert_eq!(split("a,"), vec!["a", ""]);
+    assert_eq!(split("\"\",x"), vec!["", "x"]);
+    assert_eq!(split("\"a\"b,c"), vec!["ab", "c"]);
+    assert_eq!(split("\"open,x"), vec!["open,x"]);
+    assert_eq!(split("é,\"ü\"\"\""), vec!["é", "ü\""]);
+}
+
+#[test]
+fn test_dialect_sniffing() {
+    use csv_parser::Dialect;
+    let semi = Dialect::sniff(b"nom;ville;age\nalice;Paris, FR;31\nbob;Lyon;27\n");
+    assert_eq!(semi, Dialect::new().delimiter(b';'));
+
+    let pipe = Dialect::sniff(b"1|2.5|true\n3|4.0|false\n5|6|true\n");
+    assert_eq!(pipe.delimiter, b'|');
+    assert!(!pipe.has_header);
+
+    let tsv = Dialect::sniff(b"a\tb\n1\t\"x\ty\"\n2\tz\n");
+    assert_eq!(tsv.delimiter, b'\t');
+    assert!(tsv.has_header);
+
+    let single = Dialect::sniff(b"id,name\n'1','O''Hara'\n'2','Smith'\n");
+    assert_eq!(single.quote, b'\'');
+
+    let custom = Dialect::sniff_with(b"a#b#c\n1#2#3\n", b",#");
+    assert_eq!(custom.delimiter, b'#');
+}
+
+#[test]
+fn test_parse_csv_dialect_detected_and_overridden() {
+    use csv_parser::Dialect;
+    use parser_core::ParseOptions;
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "10;20;30\n1;2;3\n4;5;6\n").unwrap();
+
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    assert_eq!(csv.dialect().delimiter, b';');
+    assert!(!csv.dialect().has_header);
+    assert_eq!(csv.record_count(), 3);
+    assert_eq!(csv.field(0, 2).unwrap(), "30");
+
+    let forced = Dialect::new().delimiter(b';').has_header(true);
+    let csv =
+        CsvParser::parse_csv_dialect(file.path(), &ParseOptions::new(), Some(forced)).unwrap();
+    assert_eq!(csv.dialect(), forced);
+    assert_eq!(csv.headers(), &["10", "20", "30"]);
+    assert_eq!(csv.field_by_name(1, "20").unwrap(), "5");
+}
+
+#[test]
+fn test_csv_headers_dedup_and_has_header() {
+    use csv_parser::dedup_names;
+    let names = |v: &[&str]| dedup_names(v.iter().map(|s| s.to_string()));
+    assert_eq!(
+        names(&["id", "", "id", "id", " x "]),
+        vec!["id", "column_2", "id_2", "id_3", "x"]
+    );
+    assert_eq!(names(&["a", "a_2", "a"]), vec!["a", "a_2", "a_3"]);
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "name,name,\nalice,bob,1\n").unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    assert_eq!(csv.headers(), &["name", "name_2", "column_3"]);
+    assert_eq!(csv.field_by_name(0, "name_2").unwrap(), "bob");
+
+    let csv = csv.with_header(false).unwrap();
+    assert_eq!(csv.headers(), &["column_1", "column_2", "column_3"]);
+    assert_eq!(csv.record_count(), 2);
+    assert_eq!(csv.field_by_name(0, "column_1").unwrap(), "name");
+}
+
+#[test]
+fn test_csv_infer_schema() {
+    use csv_parser::{Column, ColumnType};
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, "id,score,ok,day,label,empty").unwrap();
+    writeln!(file, "1,2,true,2024-01-31,a,").unwrap();
+    writeln!(file, "2,2.5,FALSE,2024-02-01T10:30:00,b,NA").unwrap();
+    writeln!(file, "3,,true,2024-02-02,3,").unwrap();
+    writeln!(file, "4,7,false,not a date,d").unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+
+    let schema = csv.infer_schema(None);
+    let col = |name: &str, ty, nullable| Column {
+        name: name.to_string(),
+        ty,
+        nullable,
+    };
+    assert_eq!(
+        schema.columns,
+        vec![
+            col("id", ColumnType::Int, false),
+            col("score", ColumnType::Float, true),
+            col("ok", ColumnType::Bool, false),
+            col("day", ColumnType::String, false),
+            col("label", ColumnType::String, false),
+            col("empty", ColumnType::String, true),
+        ]
+    );
+    assert_eq!(schema.column("score").unwrap().ty.to_string(), "float");
+
+    // Échantillon des trois premiers enregistrements : dates cohérentes
+    let sample = csv.infer_schema(Some(3));
+    assert_eq!(sample.column("day").unwrap().ty, ColumnType::Date);
+    assert!(!sample.column("id").unwrap().nullable);
+
+    assert_eq!(ColumnType::of(" 12 "), Some(ColumnType::Int));
+    assert_eq!(ColumnType::of("1e3"), Some(ColumnType::Float));
+    assert_eq!(ColumnType::of("nan"), Some(ColumnType::String));
+    assert_eq!(ColumnType::of("INF"), Some(ColumnType::String));
+    assert_eq!(ColumnType::of("-Infinity"), Some(ColumnType::String));
+    assert_eq!(ColumnType::of("1e999"), Some(ColumnType::String));
+    assert_eq!(ColumnType::of("null"), None);
+    assert_eq!(ColumnType::of("2024-13-01"), Some(ColumnType::String));
+}
+
+#[test]
+fn test_csv_deserialize_records() {
+    use rayon::prelude::*;
+    use serde::Deserialize;
+    use std::borrow::Cow;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Fruit,
+        Veg,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Row<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        note: Cow<'a, str>,
+        price: f64,
+        qty: Option<u32>,
+        kind: Kind,
+    }
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "kind,name,price,qty,note\nfruit,apple,1.5,3,\"crisp, red\"\nveg,leek,2,,\"say \"\"hi\"\"\"\n"
+    )
+    .unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    let rows: Vec<Row> = csv.deserialize().collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows[0].name, "apple");
+    assert!(matches!(rows[0].note, Cow::Borrowed("crisp, red")));
+    assert_eq!(rows[0].qty, Some(3));
+    assert_eq!(rows[1].kind, Kind::Veg);
+    assert_eq!(rows[1].qty, None);
+    assert_eq!(rows[1].note, "say \"hi\"");
+
+    let par: Vec<Row> = csv.par_deserialize().collect::<Result<_, _>>().unwrap();
+    assert_eq!(par, rows);
+
+    // Par position : tuple, ou sans en-tête
+    let (kind, name): (&str, String) = csv.deserialize_record(0).unwrap();
+    assert_eq!((kind, name.as_str()), ("fruit", "apple"));
+    let csv = csv.with_header(false).unwrap();
+    #[derive(Deserialize)]
+    struct Pos<'a> {
+        kind: &'a str,
+        _name: &'a str,
+    }
+    let first: Pos = csv.deserialize_record(0).unwrap();
+    assert_eq!(first.kind, "kind");
+}
+
+#[test]
+fn test_csv_deserialize_error_location() {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Row {
+        id: u32,
+        score: f64,
+    }
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "id,score\n1,2.5\n2,abc\n").unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+    let results: Vec<Result<Row, _>> = csv.deserialize().collect();
+    assert!(results[0].is_ok());
+    let err = results[1].as_ref().unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!(loc.line, Some(2));
+    assert_eq!(loc.byte_offset, Some(17));
+    assert_eq!(loc.column, Some(3));
+    assert_eq!(loc.path.as_deref(), Some(file.path()));
+    assert!(err.to_string().contains("field \"score\" (column 2)"));
+}
+
+#[test]
+fn test_csv_structural_validation() {
+    use csv_parser::{Dialect, IssueKind};
+    // Guillemets parasites en nombre pair par enregistrement : un nombre impair
+    // déplacerait les frontières d'enregistrements elles-mêmes.
+    let data = b"a,b,c\n1,2,3\n4,5\n6,7,8,\n9,x\"\"y,z\n\"q\"r,s,t\n10,\"multi\nline\",11\n12,13\n\"open,14\n";
+    let csv = CsvParser::parse_csv_bytes(data.to_vec()).unwrap();
+    assert_eq!(csv.dialect(), Dialect::new());
+    let report = csv.validate(10);
+    assert_eq!(report.records, 8);
+    assert_eq!(report.expected_fields, 3);
+    assert_eq!(report.field_count_mismatches, 3);
+    assert_eq!(report.trailing_delimiters, 1);
+    assert_eq!(report.stray_quotes, 2);
+    assert_eq!(report.unterminated_quotes, 1);
+    assert_eq!(report.issue_count(), 7);
+    assert!(!report.is_valid());
+
+    let found: Vec<_> = report
+        .examples
+        .iter()
+        .map(|i| (i.kind, i.record, i.line))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                IssueKind::FieldCount {
+                    expected: 3,
+                    found: 2
+                },
+                1,
+                3
+            ),
+            (IssueKind::TrailingDelimiter, 2, 4),
+            (IssueKind::StrayQuote, 3, 5),
+            (IssueKind::StrayQuote, 4, 6),
+            // L'enregistrement multi-ligne décale les numéros de ligne physiques
+            (
+                IssueKind::FieldCount {
+                    expected: 3,
+                    found: 2
+                },
+                6,
+                9
+            ),
+            (IssueKind::UnterminatedQuote, 7, 10),
+            (
+                IssueKind::FieldCount {
+                    expected: 3,
+                    found: 1
+                },
+                7,
+                10
+            ),
+        ]
+    );
+    assert_eq!(report.examples[2].byte_offset, 26);
+
+    let few = csv.validate(2);
+    assert_eq!(few.examples.len(), 2);
+    assert_eq!(few.issue_count(), 7);
+    assert!(few
+        .to_string()
+        .contains("line 3 (record 1, byte 12): expected 3 fields, found 2"));
+
+    let clean = CsvParser::parse_csv_bytes(b"a;b\n1;\"x;y\"\n".to_vec()).unwrap();
+    assert!(clean.validate(5).is_valid());
+}
+
+#[test]
+fn test_csv_column_projection() {
+    use csv_parser::ColumnRef;
+    use rayon::prelude::*;
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "a,b,c,d\n1,\"skip \"\"me\"\", please\",x,\"d,1\"\n2,,y,\"d\n2\"\n3,z\n"
+    )
+    .unwrap();
+    let csv = CsvParser::parse_csv(file.path()).unwrap();
+
+    let proj = csv.project(["d", "a", "d"]).unwrap();
+    assert_eq!(proj.names(), &["d", "a", "d"]);
+    assert_eq!(proj.len(), 3);
+    assert_eq!(proj.row(0).unwrap(), vec!["d,1", "1", "d,1"]);
+    assert_eq!(proj.row(1).unwrap(), vec!["d\n2", "2", "d\n2"]);
+    let err = proj.row(2).err().unwrap();
+    assert!(err
+        .to_string()
+        .contains("missing column 4 (record has 2 fields)"));
+    assert_eq!(err.location().unwrap().line, Some(3));
+
+    let by_index = csv.project([2usize, 0]).unwrap();
+    let rows: Vec<_> = by_index.rows().collect();
+    assert_eq!(rows[0].as_ref().unwrap(), &["x", "1"]);
+    assert_eq!(rows[1].as_ref().unwrap(), &["y", "2"]);
+    let par: Vec<_> = by_index.par_rows().collect();
+    assert_eq!(par.len(), 3);
+    assert_eq!(par[1].as_ref().unwrap(), rows[1].as_ref().unwrap());
+    assert!(par[2].is_err() && rows[2].is_err());
+
+    let mixed = csv
+        .project([ColumnRef::Name("b"), ColumnRef::Index(1)])
+        .unwrap();
+    assert_eq!(mixed.row(0).unwrap(), vec!["skip \"me\", please"; 2]);
+    assert!(csv.project(["nope"]).is_err());
+}
+
+#[test]
+fn test_csv_column_profile() {
+    use csv_parser::ColumnType;
+    let mut data = String::from("id,price,city,note\n");
+    for i in 0..5000 {
+        let city = ["Paris", "Lyon", "Lille"][i % 3];
+        let price = if i % 10 == 0 {
+            String::new()
+        } else {
+            format!("{}.5", i % 7)
+        };
+        data.push_str(&format!("{i},{price},{city},NA\n"));
+    }
+    data.push_str("5000,1,Nice\n");
+    let csv = CsvParser::parse_csv_bytes(data.into_bytes()).unwrap();
+    let profile = csv.profile(2);
+    assert_eq!(profile.records, 5001);
+
+    let id = &profile.columns[0];
+    assert_eq!(id.ty, ColumnType::Int);
+    assert_eq!(id.nulls, 0);
+    let num = id.numeric.unwrap();
+    assert_eq!((num.min, num.max), (0.0, 5000.0));
+    assert!((num.mean - 2500.0).abs() < 1e-9);
+    assert!((num.std_dev - 1443.6).abs() < 0.1);
+    // HyperLogLog : quelques pourcents d'erreur
+    assert!((4800..=5200).contains(&id.distinct), "{}", id.distinct);
+    assert_eq!(id.length.unwrap().max, 4);
+
+    let price = &profile.columns[1];
+    assert_eq!(price.ty, ColumnType::Float);
+    assert_eq!((price.nulls, price.empty), (500, 500));
+    assert!((price.null_ratio - 500.0 / 5001.0).abs() < 1e-12);
+    assert_eq!(price.distinct, 8);
+
+    let city = &profile.columns[2];
+    assert_eq!(city.ty, ColumnType::String);
+    assert!(city.numeric.is_none());
+    assert_eq!(city.distinct, 4);
+    assert_eq!(city.top.len(), 2);
+    // Égalité de comptes : ordre alphabétique
+    let top: Vec<_> = city
+        .top
+        .iter()
+        .map(|t| (t.value.as_str(), t.count))
+        .collect();
+    assert_eq!(top, vec![("Lyon", 1667), ("Paris", 1667)]);
+    assert_eq!(city.length.unwrap().min, 4);
+
+    // Champ absent dans le dernier enregistrement : compté comme null
+    let note = &profile.columns[3];
+    assert_eq!((note.nulls, note.empty), (5001, 0));
+    assert!(note.length.is_none() && note.top.is_empty());
+}
+
+#[test]
+fn test_csv_writer() {
+    use csv_parser::{CsvWriter, Dialect};
+    use serde::Serialize;
+
+    let mut w = CsvWriter::new(Vec::new(), Dialect::new());
+    w.write_record(["a", "b,c", "say \"hi\"", "x\ny", ""])
+        .unwrap();
+    assert_eq!(w.records_written(), 1);
+    let out = w.into_inner().unwrap();
+    assert_eq!(out, b"a,\"b,c\",\"say \"\"hi\"\"\",\"x\ny\",\n");
+
+    // Relecture : mêmes champs
+    let csv = CsvParser::parse_csv_dialect(
+        file_with(&out).path(),
+        &parser_core::ParseOptions::new(),
+        Some(Dialect::new().has_header(false)),
+    )
+    .unwrap();
+    assert_eq!(
+        csv.fields(0).unwrap().collect::<Vec<_>>(),
+        vec!["a", "b,c", "say \"hi\"", "x\ny", ""]
+    );
+
+    #[derive(Serialize)]
+    enum Kind {
+        Fruit,
+    }
+    #[derive(Serialize)]
+    struct Row<'a> {
+        name: &'a str,
+        price: f64,
+        qty: Option<u32>,
+        kind: Kind,
+    }
+    let mut w = CsvWriter::new(Vec::new(), Dialect::new().delimiter(b';'));
+    w.serialize(&Row {
+        name: "pomme; rouge",
+        price: 1.5,
+        qty: None,
+        kind: Kind::Fruit,
+    })
+    .unwrap();
+    w.serialize(&("poire", 2, Some(3))).unwrap();
+    assert!(w.serialize(&vec![vec![1]]).is_err());
+    assert_eq!(
+        String::from_utf8(w.into_inner().unwrap()).unwrap(),
+        "name;price;qty;kind\n\"pomme; rouge\";1.5;;Fruit\npoire;2;3\n"
+    );
+}
+
+#[test]
+fn test_csv_writer_copies_raw_fields() {
+    use csv_parser::{CsvWriter, Dialect};
+    let data = "id,note,x\n1,\"a \"\"b\"\", c\",p\n2,plain;semi,q\n";
+    let csv = CsvParser::parse_csv(file_with(data.as_bytes()).path()).unwrap();
+
+    // Même dialecte : enregistrements filtrés recopiés octet pour octet
+    let mut w = CsvWriter::new(Vec::new(), csv.dialect());
+    w.write_record(csv.headers()).unwrap();
+    for record in csv.records() {
+        w.write_raw_record(&record.unwrap()).unwrap();
+    }
+    assert_eq!(w.into_inner().unwrap(), data.as_bytes());
+
+    // Projection : champs bruts recopiés, ou réécrits pour un autre dialecte
+    let proj = csv.project(["note", "id"]).unwrap();
+    let mut same = CsvWriter::new(Vec::new(), csv.dialect());
+    let mut semi = CsvWriter::new(Vec::new(), Dialect::new().delimiter(b';'));
+    for i in 0..proj.len() {
+        same.write_projected(&proj, i).unwrap();
+        semi.write_projected(&proj, i).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(same.into_inner().unwrap()).unwrap(),
+        "\"a \"\"b\"\", c\",1\nplain;semi,2\n"
+    );
+    assert_eq!(
+        String::from_utf8(semi.into_inner().unwrap()).unwrap(),
+        "\"a \"\"b\"\", c\";1\n\"plain;semi\";2\n"
+    );
+}
+
+fn file_with(data: &[u8]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(data).unwrap();
+    file
+}
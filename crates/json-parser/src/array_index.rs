@@ -0,0 +1,232 @@
+//! Index parallèle des éléments d'un tableau JSON à la racine.
+//!
+//! Scan structurel en trois temps, sur des chunks traités en parallèle :
+//! 1. état chaîne (dedans / dehors) en fin de chunk pour chaque état de départ,
+//!    en sautant d'un `"` ou `\` au suivant (memchr, SIMD) ;
+//! 2. composition séquentielle : état réel au début de chaque chunk ;
+//! 3. profondeur et virgules de niveau 1, hors chaînes, puis raccord des profondeurs.
+//!
+//! Aucun chunk ne commence juste après un `\`, donc jamais au milieu d'un échappement.
+//! Le résultat ne dépend pas de la taille des chunks.
+
+use crate::array_stream::{element_error, Position};
+use parser_core::{JsonErrorKind, LineIndex, ParseError, ParseOptions};
+use rayon::prelude::*;
+use serde::de::IgnoredAny;
+
+/// Bornes [début, fin) de chunks d'environ `chunk_size` octets, jamais juste après un `\`.
+fn chunk_bounds(data: &[u8], from: usize, chunk_size: usize) -> Vec<(usize, usize)> {
+    let chunk_size = chunk_size.max(1);
+    let mut bounds = Vec::with_capacity((data.len() - from) / chunk_size + 1);
+    let mut start = from;
+    while start < data.len() {
+        let mut end = start.saturating_add(chunk_size).min(data.len());
+        while end < data.len() && data[end - 1] == b'\\' {
+            end += 1;
+        }
+        bounds.push((start, end));
+        start = end;
+    }
+    bounds
+}
+
+/// Prochain `"` (ou `\` dans une chaîne) à partir de `i`.
+#[inline]
+fn next_special(chunk: &[u8], i: usize, in_string: bool) -> Option<usize> {
+    let rest = &chunk[i..];
+    if in_string {
+        memchr::memchr2(b'"', b'\\', rest)
+    } else {
+        memchr::memchr(b'"', rest)
+    }
+    .map(|p| i + p)
+}
+
+/// État chaîne en fin de `chunk` selon l'état de départ.
+fn string_state_after(chunk: &[u8], mut in_string: bool) -> bool {
+    let mut i = 0;
+    while let Some(p) = next_special(chunk, i, in_string) {
+        if chunk[p] == b'\\' {
+            i = p + 2;
+        } else {
+            in_string = !in_string;
+            i = p + 1;
+        }
+        if i >= chunk.len() {
+            break;
+        }
+    }
+    in_string
+}
+
+/// Bilan structurel d'un chunk, profondeurs relatives à son début.
+struct Summary {
+    depth: i64,
+    /// Profondeur minimale atteinte.
+    min: i64,
+    /// Virgules à la profondeur `min`.
+    at_min: Vec<usize>,
+    /// Virgules à la profondeur `min + 1`.
+    above_min: Vec<usize>,
+}
+
+fn summarize(chunk: &[u8], base: usize, mut in_string: bool) -> Summary {
+    let mut s = Summary {
+        depth: 0,
+        min: 0,
+        at_min: Vec::new(),
+        above_min: Vec::new(),
+    };
+    let mut i = 0;
+    while i < chunk.len() {
+        if in_string {
+            match next_special(chunk, i, true) {
+                Some(p) if chunk[p] == b'\\' => i = p + 2,
+                Some(p) => {
+                    in_string = false;
+                    i = p + 1;
+                }
+                None => break,
+            }
+            continue;
+        }
+        match chunk[i] {
+            b'"' => in_string = true,
+            b'[' | b'{' => s.depth += 1,
+            b']' | b'}' => {
+                s.depth -= 1;
+                if s.depth < s.min {
+                    s.min = s.depth;
+                    s.above_min = std::mem::take(&mut s.at_min);
+                }
+            }
+            b',' if s.depth == s.min => s.at_min.push(base + i),
+            b',' if s.depth == s.min + 1 => s.above_min.push(base + i),
+            _ => {}
+        }
+        i += 1;
+    }
+    s
+}
+
+fn syntax_error(data: &[u8], offset: usize, message: &str) -> ParseError {
+    ParseError::Json {
+        kind: JsonErrorKind::Syntax,
+        message: message.to_string(),
+        location: Box::new(Position::at(data, offset).location()),
+    }
+}
+
+/// Plage de chaque élément du tableau qui commence à `from` (blancs autour retirés).
+///
+/// Seule la structure est vérifiée (crochets de tête et de fin, équilibre) : voir
+/// [`check_elements`] pour la syntaxe de chaque élément.
+pub(crate) fn index_array(
+    data: &[u8],
+    from: usize,
+    chunk_size: usize,
+) -> Result<LineIndex, ParseError> {
+    let open = from
+        + data[from..]
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(0);
+    if data.get(open) != Some(&b'[') {
+        return Err(syntax_error(data, open, "expected `[`"));
+    }
+    let close = data.trim_ascii_end().len().saturating_sub(1);
+    if close <= open || data[close] != b']' {
+        return Err(syntax_error(
+            data,
+            close + 1,
+            "expected `]` at end of array",
+        ));
+    }
+    let bounds = chunk_bounds(data, open + 1, chunk_size);
+    let transitions: Vec<[bool; 2]> = bounds
+        .par_iter()
+        .map(|&(s, e)| {
+            let chunk = &data[s..e];
+            [
+                string_state_after(chunk, false),
+                string_state_after(chunk, true),
+            ]
+        })
+        .collect();
+    let mut in_string = false;
+    let starts: Vec<bool> = transitions
+        .iter()
+        .map(|t| {
+            let start = in_string;
+            in_string = t[start as usize];
+            start
+        })
+        .collect();
+    let summaries: Vec<Summary> = bounds
+        .par_iter()
+        .zip(starts)
+        .map(|(&(s, e), in_string)| summarize(&data[s..e], s, in_string))
+        .collect();
+
+    // Profondeur 1 = dans le tableau, hors de tout élément.
+    let mut depth = 1i64;
+    let mut commas = Vec::new();
+    for s in summaries {
+        if depth + s.min < 0 {
+            return Err(syntax_error(data, close, "unbalanced brackets"));
+        }
+        match 1 - depth {
+            level if level == s.min => commas.extend(s.at_min),
+            level if level == s.min + 1 => commas.extend(s.above_min),
+            _ => {}
+        }
+        depth += s.depth;
+    }
+    if depth != 0 || in_string {
+        return Err(syntax_error(data, close, "unbalanced brackets or string"));
+    }
+
+    let mut seps = Vec::with_capacity(commas.len() + 2);
+    seps.push(open);
+    seps.extend(commas.into_iter().filter(|&c| c < close));
+    seps.push(close);
+    let ranges: Vec<(u64, u64)> = seps
+        .par_windows(2)
+        .with_min_len(4096)
+        .map(|w| {
+            let inner = &data[w[0] + 1..w[1]];
+            let lead = inner.len() - inner.trim_ascii_start().len();
+            let len = inner.trim_ascii().len();
+            ((w[0] + 1 + lead) as u64, len as u64)
+        })
+        .collect();
+    let mut offsets = LineIndex::with_capacity(data.len() as u64, ranges.len());
+    // `[ ]` : aucun élément
+    if !(ranges.len() == 1 && ranges[0].1 == 0) {
+        for (start, len) in ranges {
+            offsets.push(start, len);
+        }
+    }
+    Ok(offsets)
+}
+
+/// Vérifie la syntaxe de chaque élément en parallèle, sans construire de `Value` ;
+/// la première erreur dans l'ordre du fichier est renvoyée.
+pub(crate) fn check_elements(
+    data: &[u8],
+    offsets: &LineIndex,
+    opts: &ParseOptions,
+) -> Result<(), ParseError> {
+    let bad = opts.install(|| {
+        (0..offsets.len())
+            .into_par_iter()
+            .with_min_len(1024)
+            .find_map_first(|i| {
+                let (start, len) = offsets.get(i).unwrap();
+                let bytes = &data[start as usize..(start + len) as usize];
+                let e = serde_json::from_slice::<IgnoredAny>(bytes).err()?;
+                Some(element_error(bytes, Position::at(data, start as usize), e))
+            })
+    });
+    bad.map_or(Ok(()), Err)
+}
@@ -0,0 +1,361 @@
+//! Lecture élément par élément d'un tableau JSON, à la racine ou sous un chemin
+//! (`$.data.items`).
+//!
+//! Mémoire bornée : seul l'élément courant est gardé (texte brut, puis valeur). Le flux
+//! est balayé par blocs (`fill_buf`) en suivant chaînes, échappements et imbrication,
+//! ce qui délimite chaque élément sans le parser ; serde ne voit qu'un élément à la fois.
+//! Ce qui suit le tableau n'est pas lu.
+
+use crate::json_error;
+use parser_core::{JsonErrorKind, Location, ParseError};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// Position courante dans le flux.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Position {
+    pub(crate) offset: u64,
+    /// Ligne courante (base 0).
+    pub(crate) line: usize,
+    /// Offset du début de la ligne courante.
+    pub(crate) line_start: u64,
+}
+
+impl Position {
+    /// Position de l'octet `offset` de `data` (lignes comptées depuis le début).
+    pub(crate) fn at(data: &[u8], offset: usize) -> Self {
+        let head = &data[..offset];
+        Position {
+            offset: offset as u64,
+            line: memchr::memchr_iter(b'\n', head).count(),
+            line_start: memchr::memrchr(b'\n', head).map_or(0, |p| p as u64 + 1),
+        }
+    }
+
+    /// Avance sur `bytes`, qui viennent d'être consommés.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) {
+        if let Some(last) = memchr::memrchr(b'\n', bytes) {
+            self.line += memchr::memchr_iter(b'\n', bytes).count();
+            self.line_start = self.offset + last as u64 + 1;
+        }
+        self.offset += bytes.len() as u64;
+    }
+
+    pub(crate) fn location(&self) -> Location {
+        Location {
+            line: Some(self.line),
+            byte_offset: Some(self.offset),
+            column: Some((self.offset - self.line_start) as usize + 1),
+            ..Default::default()
+        }
+    }
+}
+
+/// Saute les blancs et renvoie le premier octet significatif, sans le consommer.
+pub(crate) fn skip_whitespace<R: BufRead>(
+    reader: &mut R,
+    pos: &mut Position,
+) -> std::io::Result<Option<u8>> {
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        let n = chunk
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(chunk.len());
+        let next = chunk.get(n).copied();
+        pos.advance(&chunk[..n]);
+        reader.consume(n);
+        if next.is_some() {
+            return Ok(next);
+        }
+    }
+}
+
+/// Délimitation d'une valeur JSON octet par octet.
+#[derive(Default)]
+struct Scan {
+    depth: u32,
+    in_string: bool,
+    escaped: bool,
+    scalar: bool,
+}
+
+impl Scan {
+    /// Fin (exclue) de la valeur dans `chunk`, si elle s'y termine.
+    fn feed(&mut self, chunk: &[u8]) -> Option<usize> {
+        let mut i = 0;
+        while i < chunk.len() {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                    i += 1;
+                    continue;
+                }
+                let p = memchr::memchr2(b'"', b'\\', &chunk[i..])?;
+                i += p;
+                if chunk[i] == b'\\' {
+                    self.escaped = true;
+                } else {
+                    self.in_string = false;
+                    if self.depth == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                i += 1;
+                continue;
+            }
+            match chunk[i] {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' if self.depth == 0 => return Some(i),
+                b'}' | b']' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                b',' | b' ' | b'\t' | b'\n' | b'\r' if self.depth == 0 => return Some(i),
+                _ => self.scalar |= self.depth == 0,
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Chemin pas encore parcouru.
+    Start,
+    /// Juste après `[`.
+    First,
+    /// Après un élément.
+    Next,
+    Done,
+}
+
+/// Itérateur sur les éléments d'un tableau JSON lu dans un flux.
+///
+/// Les erreurs sont localisées (ligne, colonne, octet) ; l'itération s'arrête à la première.
+pub struct ArrayStream<R, T = Value> {
+    reader: R,
+    /// Clés à traverser depuis la racine jusqu'au tableau.
+    keys: Vec<String>,
+    json_path: String,
+    state: State,
+    pos: Position,
+    buf: Vec<u8>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> ArrayStream<R, T> {
+    /// Itère sur le tableau désigné par `json_path` : `$` (racine) ou `$.a.b` (clés
+    /// d'objets successives).
+    pub fn new(reader: R, json_path: &str) -> Result<Self, ParseError> {
+        let rest = json_path.strip_prefix('$').unwrap_or(json_path);
+        let rest = rest.strip_prefix('.').unwrap_or(rest);
+        let keys: Vec<String> = match rest {
+            "" => Vec::new(),
+            rest => rest.split('.').map(str::to_string).collect(),
+        };
+        if keys.iter().any(String::is_empty) {
+            return Err(ParseError::Format(format!(
+                "invalid JSON path: {json_path}"
+            )));
+        }
+        Ok(Self::resume(reader, keys, json_path, Position::default()))
+    }
+
+    /// Reprend un flux déjà avancé jusqu'à `pos`.
+    pub(crate) fn resume(reader: R, keys: Vec<String>, json_path: &str, pos: Position) -> Self {
+        ArrayStream {
+            reader,
+            keys,
+            json_path: json_path.to_string(),
+            state: State::Start,
+            pos,
+            buf: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn error(&self, kind: JsonErrorKind, message: String) -> ParseError {
+        ParseError::Json {
+            kind,
+            message,
+            location: Box::new(self.pos.location()),
+        }
+    }
+
+    fn syntax(&self, expected: &str) -> ParseError {
+        self.error(JsonErrorKind::Syntax, format!("expected {expected}"))
+    }
+
+    fn eof(&self) -> ParseError {
+        self.error(JsonErrorKind::Eof, "unexpected end of input".to_string())
+    }
+
+    fn skip_ws(&mut self) -> Result<Option<u8>, ParseError> {
+        Ok(skip_whitespace(&mut self.reader, &mut self.pos)?)
+    }
+
+    /// Consomme un octet structurel (jamais une fin de ligne).
+    fn bump(&mut self) {
+        self.reader.consume(1);
+        self.pos.offset += 1;
+    }
+
+    fn expect(&mut self, byte: u8, expected: &str) -> Result<(), ParseError> {
+        match self.skip_ws()? {
+            Some(b) if b == byte => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => Err(self.syntax(expected)),
+            None => Err(self.eof()),
+        }
+    }
+
+    /// Consomme la valeur qui commence ici, copiée dans `buf` si `keep`.
+    fn scan_value(&mut self, keep: bool) -> Result<(), ParseError> {
+        let mut scan = Scan::default();
+        loop {
+            let chunk = self.reader.fill_buf()?;
+            if chunk.is_empty() {
+                return if scan.scalar && scan.depth == 0 && !scan.in_string {
+                    Ok(())
+                } else {
+                    Err(self.eof())
+                };
+            }
+            let (n, done) = match scan.feed(chunk) {
+                Some(n) => (n, true),
+                None => (chunk.len(), false),
+            };
+            if keep {
+                self.buf.extend_from_slice(&chunk[..n]);
+            }
+            self.pos.advance(&chunk[..n]);
+            self.reader.consume(n);
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Descend le long du chemin jusqu'au `[` du tableau.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        let keys = std::mem::take(&mut self.keys);
+        for key in &keys {
+            self.expect(b'{', "an object")?;
+            loop {
+                match self.skip_ws()? {
+                    Some(b'"') => {}
+                    Some(b'}') => return Err(self.not_found()),
+                    Some(_) => return Err(self.syntax("an object key")),
+                    None => return Err(self.eof()),
+                }
+                self.buf.clear();
+                self.scan_value(true)?;
+                let name: String =
+                    serde_json::from_slice(&self.buf).map_err(|_| self.syntax("an object key"))?;
+                self.expect(b':', "`:`")?;
+                if self.skip_ws()?.is_none() {
+                    return Err(self.eof());
+                }
+                if name == *key {
+                    break;
+                }
+                self.scan_value(false)?;
+                match self.skip_ws()? {
+                    Some(b',') => self.bump(),
+                    Some(b'}') => return Err(self.not_found()),
+                    Some(_) => return Err(self.syntax("`,` or `}`")),
+                    None => return Err(self.eof()),
+                }
+            }
+        }
+        match self.skip_ws()? {
+            Some(b'[') => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => Err(self.error(
+                JsonErrorKind::Data,
+                format!("expected an array at {}", self.json_path),
+            )),
+            None => Err(self.eof()),
+        }
+    }
+
+    fn not_found(&self) -> ParseError {
+        self.error(
+            JsonErrorKind::Data,
+            format!("path {} not found", self.json_path),
+        )
+    }
+
+    fn next_element(&mut self) -> Result<Option<T>, ParseError> {
+        match self.state {
+            State::Done => return Ok(None),
+            State::Start => {
+                self.enter()?;
+                self.state = State::First;
+            }
+            State::First | State::Next => {}
+        }
+        match self.skip_ws()? {
+            Some(b']') => {
+                self.bump();
+                self.state = State::Done;
+                return Ok(None);
+            }
+            Some(b',') if self.state == State::Next => {
+                self.bump();
+                if self.skip_ws()?.is_none() {
+                    return Err(self.eof());
+                }
+            }
+            Some(_) if self.state == State::First => {}
+            Some(_) => return Err(self.syntax("`,` or `]`")),
+            None => return Err(self.eof()),
+        }
+        self.state = State::Next;
+        let start = self.pos;
+        self.buf.clear();
+        self.scan_value(true)?;
+        serde_json::from_slice(&self.buf)
+            .map(Some)
+            .map_err(|e| element_error(&self.buf, start, e))
+    }
+}
+
+/// Erreur serde_json dans un élément commençant à `start` (colonnes relatives à la ligne).
+pub(crate) fn element_error(buf: &[u8], start: Position, e: serde_json::Error) -> ParseError {
+    let mut err = json_error(buf, start.line, start.offset, e);
+    if let ParseError::Json { location, .. } = &mut err {
+        if let (Some(line), Some(offset)) = (location.line, location.byte_offset) {
+            if line == start.line {
+                location.column = Some((offset - start.line_start) as usize + 1);
+            }
+        }
+    }
+    err
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for ArrayStream<R, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next_element().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            self.state = State::Done;
+        }
+        item
+    }
+}
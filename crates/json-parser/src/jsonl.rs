@@ -0,0 +1,175 @@
+//! Document JSONL zero-copy : chaque enregistrement pointe directement dans le mmap
+//! (ou le buffer décompressé), sans re-sérialisation.
+//!
+//! La syntaxe de chaque ligne est vérifiée en parallèle sans construire de `Value`
+//! (`IgnoredAny`) ; les enregistrements sont désérialisés à la demande. Les lignes
+//! vides sont écartées de l'index.
+
+use crate::json_error;
+use parser_core::{
+    chunks, Document, DocumentData, JsonErrorKind, LineIndex, ParseError, ParseOptions,
+};
+use rayon::prelude::*;
+use serde::de::{Deserialize, DeserializeOwned, IgnoredAny};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Document JSONL : un enregistrement par ligne non vide.
+pub struct JsonlDocument {
+    doc: Document,
+    /// Pour chaque ligne vide, l'index de l'enregistrement qui la suit (croissant) :
+    /// la ligne du fichier de l'enregistrement `i` est `i` + vides à index <= `i`.
+    blank: Vec<usize>,
+    path: Option<PathBuf>,
+}
+
+impl JsonlDocument {
+    /// Indexe les lignes non vides de `data` en parallèle (UTF-8 selon `opts`), sans
+    /// vérifier la syntaxe JSON : voir [`JsonlDocument::check`].
+    pub(crate) fn index(data: DocumentData, opts: &ParseOptions) -> Result<Self, ParseError> {
+        let mut blank = Vec::new();
+        let doc = Document::from_data_with(data, opts, |bytes, bom| {
+            let lines = chunks::index_lines_from(bytes, bom, opts.chunk_size, opts.line_terminator);
+            blank = (0..lines.len())
+                .into_par_iter()
+                .with_min_len(4096)
+                .filter(|&i| {
+                    let (start, len) = lines.get(i).unwrap();
+                    bytes[start as usize..(start + len) as usize]
+                        .trim_ascii()
+                        .is_empty()
+                })
+                .collect();
+            if blank.is_empty() {
+                return lines;
+            }
+            let mut kept = LineIndex::with_capacity(bytes.len() as u64, lines.len() - blank.len());
+            let mut skip = blank.iter().peekable();
+            for (i, (start, len)) in lines.iter().enumerate() {
+                if skip.next_if_eq(&&i).is_none() {
+                    kept.push(start, len);
+                }
+            }
+            kept
+        })?;
+        // Ligne vide `k` du fichier (base 0) -> index de l'enregistrement suivant
+        for (k, b) in blank.iter_mut().enumerate() {
+            *b -= k;
+        }
+        Ok(JsonlDocument {
+            doc,
+            blank,
+            path: None,
+        })
+    }
+
+    /// Vérifie la syntaxe de chaque enregistrement en parallèle ; la première erreur
+    /// (dans l'ordre du fichier) est renvoyée.
+    ///
+    /// `false` si le premier enregistrement est une valeur incomplète : JSON indenté
+    /// sur plusieurs lignes, pas du JSONL.
+    pub(crate) fn check(&self, opts: &ParseOptions) -> Result<bool, ParseError> {
+        if self.is_empty() {
+            return Ok(true);
+        }
+        match self.check_record(0) {
+            Err(ParseError::Json {
+                kind: JsonErrorKind::Eof,
+                ..
+            }) => return Ok(false),
+            r => r?,
+        }
+        let bad = opts.install(|| {
+            (1..self.len())
+                .into_par_iter()
+                .with_min_len(1024)
+                .find_map_first(|i| self.check_record(i).err())
+        });
+        match bad {
+            Some(e) => Err(e),
+            None => Ok(true),
+        }
+    }
+
+    fn check_record(&self, i: usize) -> Result<(), ParseError> {
+        let (start, bytes) = self.raw_bytes(i);
+        serde_json::from_slice::<IgnoredAny>(bytes)
+            .map(drop)
+            .map_err(|e| self.record_error(bytes, start, i, e))
+    }
+
+    pub(crate) fn with_path(mut self, path: &Path) -> Self {
+        self.doc = self.doc.with_path(path);
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Nombre d'enregistrements.
+    pub fn len(&self) -> usize {
+        self.doc.line_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Ligne du fichier (base 0) de l'enregistrement `i`.
+    fn line_number(&self, i: usize) -> usize {
+        i + self.blank.partition_point(|&b| b <= i)
+    }
+
+    fn raw_bytes(&self, i: usize) -> (u64, &[u8]) {
+        let (start, len) = self.doc.offsets.get(i).unwrap();
+        (
+            start,
+            &self.doc.data.as_bytes()[start as usize..(start + len) as usize],
+        )
+    }
+
+    fn record_error(&self, bytes: &[u8], start: u64, i: usize, e: serde_json::Error) -> ParseError {
+        let err = json_error(bytes, self.line_number(i), start, e);
+        match &self.path {
+            Some(path) => err.with_path(path),
+            None => err,
+        }
+    }
+
+    /// Texte brut de l'enregistrement `i`, tel qu'il figure dans le fichier.
+    pub fn raw(&self, i: usize) -> Result<&str, ParseError> {
+        self.doc.get_line(i)
+    }
+
+    /// Désérialise l'enregistrement `i` ; les chaînes sans échappement peuvent être
+    /// empruntées au document.
+    pub fn get<'a, T: Deserialize<'a>>(&'a self, i: usize) -> Result<T, ParseError> {
+        let text = self.raw(i)?;
+        let (start, _) = self.doc.offsets.get(i).unwrap();
+        serde_json::from_str(text).map_err(|e| self.record_error(text.as_bytes(), start, i, e))
+    }
+
+    /// Enregistrement `i` en [`Value`].
+    pub fn value(&self, i: usize) -> Result<Value, ParseError> {
+        self.get(i)
+    }
+
+    /// Itérateur sur les enregistrements désérialisés à la volée.
+    pub fn records<T: DeserializeOwned>(&self) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    /// Comme [`JsonlDocument::records`], en parallèle (ordre conservé).
+    pub fn par_records<T: DeserializeOwned + Send>(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = Result<T, ParseError>> + '_ {
+        (0..self.len()).into_par_iter().map(|i| self.get(i))
+    }
+
+    /// Document sous-jacent : une ligne par enregistrement.
+    pub fn document(&self) -> &Document {
+        &self.doc
+    }
+
+    pub fn into_document(self) -> Document {
+        self.doc
+    }
+}
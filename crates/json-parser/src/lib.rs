@@ -1,14 +1,38 @@
 //! Parser JSON haute performance pour petits et très gros fichiers.
 //!
 //! - Utilise serde_json pour les petits fichiers (chargement complet)
+//! - simd-json désérialise directement vers le type demandé (`Value`, [`OwnedValue`],
+//!   structure utilisateur), sans conversion intermédiaire
 //! - Parsing streaming pour les gros fichiers (JSONL ou incrémental)
+//! - Tableaux massifs lus élément par élément, à la racine ou sous un chemin (`$.data.items`)
+//! - JSONL en Document zero-copy sur le mmap, enregistrements parsés à la demande
+//! - Tableau à la racine indexé en parallèle : une "ligne" par élément, dans le mmap
+//! - Mode JSONL tolérant : lignes invalides écartées (budget d'erreurs, quarantaine)
 //! - API similaire à txt-parser
 
-use memmap2::MmapOptions;
-use parser_core::{DocumentParser, ParseError};
+mod array_index;
+mod array_stream;
+mod jsonl;
+mod tolerant;
+
+pub use array_stream::ArrayStream;
+pub use jsonl::JsonlDocument;
+/// Représentation native de simd-json, la plus rapide à construire.
+pub use simd_json::OwnedValue;
+pub use tolerant::{Rejected, TolerantJsonl, TolerantOptions};
+
+use array_stream::{skip_whitespace, Position};
+use memchr::{memchr, memchr_iter};
+use parser_core::decompress::{self, Compression};
+use parser_core::{
+    Document, DocumentData, DocumentParser, JsonErrorKind, LineIndex, Location, ParseError,
+    ParseOptions,
+};
 use rayon::prelude::*;
 use serde::de::DeserializeOwned;
+use serde_json::error::Category;
 use serde_json::Value;
+use simd_json::ErrorType;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
@@ -31,106 +55,248 @@ impl Iterator for JsonObjectIter {
 }
 
 impl JsonParser {
-    /// Parse un fichier JSON (petit ou gros). Utilise simd-json si possible, sinon fallback serde_json.
+    /// Parse un fichier JSON (petit ou gros) ou JSONL avec simd-json.
+    ///
+    /// Un document unique est parsé en place dans le buffer du fichier (aucune copie).
     pub fn parse(path: &Path) -> Result<Vec<Value>, ParseError> {
-        let data = std::fs::read(path)?;
-        let text = std::str::from_utf8(&data)?;
-        if text.lines().count() > 1 {
-            let mut values = Vec::new();
-            for line in text.lines() {
-                if !line.trim().is_empty() {
-                    let mut line_bytes = line.as_bytes().to_vec();
-                    match simd_json::to_owned_value(&mut line_bytes) {
-                        Ok(v) => values.push(
-                            serde_json::to_value(v)
-                                .map_err(|e| ParseError::Format(e.to_string()))?,
-                        ),
-                        Err(_) => values.push(
-                            serde_json::from_str(line)
-                                .map_err(|e| ParseError::Format(e.to_string()))?,
-                        ),
-                    }
-                }
+        let mut data = decompress::read_file(path)?;
+        let text = utf8_text(&data).map_err(|e| e.with_path(path))?;
+        if let Some(values) = Self::parse_lines(text).map_err(|e| e.with_path(path))? {
+            return Ok(values);
+        }
+        let value =
+            simd_json::serde::from_slice(&mut data).map_err(|e| simd_error(e).with_path(path))?;
+        Ok(vec![value])
+    }
+
+    /// Parse un contenu JSON/JSONL déjà en mémoire (même stratégie que [`JsonParser::parse`]).
+    pub fn parse_slice(data: &[u8]) -> Result<Vec<Value>, ParseError> {
+        let text = utf8_text(data)?;
+        if let Some(values) = Self::parse_lines(text)? {
+            return Ok(values);
+        }
+        Ok(vec![Self::parse_whole(data)?])
+    }
+
+    /// Une valeur par ligne non vide si `text` compte plusieurs lignes ; `None` pour un
+    /// document unique, y compris indenté sur plusieurs lignes (première ligne incomplète).
+    fn parse_lines(text: &str) -> Result<Option<Vec<Value>>, ParseError> {
+        if text.lines().nth(1).is_none() {
+            return Ok(None);
+        }
+        let mut scratch = LineScratch::default();
+        let mut values = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
             }
-            Ok(values)
-        } else {
-            let mut data_mut = data.clone();
-            match simd_json::to_owned_value(&mut data_mut) {
-                Ok(v) => Ok(vec![
-                    serde_json::to_value(v).map_err(|e| ParseError::Format(e.to_string()))?
-                ]),
-                Err(_) => {
-                    Ok(vec![serde_json::from_slice(&data)
-                        .map_err(|e| ParseError::Format(e.to_string()))?])
-                }
+            match scratch.parse(line) {
+                Ok(v) => values.push(v),
+                Err(e) if values.is_empty() && e.classify() == Category::Eof => return Ok(None),
+                Err(e) => return Err(line_error(text, line, idx, e)),
             }
         }
+        Ok(Some(values))
     }
+
+    /// Document unique : simd-json sur une copie (il réécrit son entrée, l'appelant garde
+    /// la sienne), serde_json en repli pour une erreur localisée.
+    fn parse_whole<T: DeserializeOwned>(data: &[u8]) -> Result<T, ParseError> {
+        match simd_json::serde::from_slice(&mut data.to_vec()) {
+            Ok(v) => Ok(v),
+            Err(_) => serde_json::from_slice(data).map_err(|e| json_error(data, 0, 0, e)),
+        }
+    }
+
     /// Parse un fichier JSONL (une valeur JSON par ligne) en parallèle, mmap + rayon.
     pub fn parse_jsonl_parallel(path: &Path) -> Result<Vec<Value>, ParseError> {
-        let file = File::open(path)?;
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let data = &mmap[..];
-        let text = std::str::from_utf8(data)?;
+        let backing = decompress::open_data(path)?;
+        let text = utf8_text(backing.as_bytes()).map_err(|e| e.with_path(path))?;
         // Découpe en lignes (sans allocation intermédiaire)
         let lines: Vec<&str> = text.lines().collect();
         let values: Result<Vec<_>, _> = lines
             .par_iter()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| {
-                serde_json::from_str::<Value>(line).map_err(|e| ParseError::Format(e.to_string()))
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str::<Value>(line)
+                    .map_err(|e| line_error(text, line, idx, e).with_path(path))
             })
             .collect();
         values
     }
     /// Parse un fichier JSON massif (unique objet/array) en streaming (faible RAM).
     pub fn parse_streaming<T: DeserializeOwned>(path: &Path) -> Result<T, ParseError> {
-        let file = File::open(path)?;
-        let reader = std::io::BufReader::new(file);
+        let reader = std::io::BufReader::new(decompress::decompress_reader(File::open(path)?)?);
         let mut deser = serde_json::Deserializer::from_reader(reader);
-        let v = T::deserialize(&mut deser).map_err(|e| ParseError::Format(e.to_string()))?;
+        let v = T::deserialize(&mut deser).map_err(|e| stream_error(e).with_path(path))?;
         Ok(v)
     }
     /// Choix auto du mode selon la taille et le format (JSONL ou massif).
     pub fn parse_auto(path: &Path) -> Result<Vec<Value>, ParseError> {
+        Self::parse_auto_with(path, &ParseOptions::default())
+    }
+
+    /// Comme [`JsonParser::parse_auto`], avec le seuil mémoire et le pool de `opts`.
+    ///
+    /// Au-delà du seuil, un tableau à la racine est rendu élément par élément.
+    pub fn parse_auto_with(path: &Path, opts: &ParseOptions) -> Result<Vec<Value>, ParseError> {
+        Self::auto_values(path, opts)?.collect()
+    }
+
+    /// Valeurs du fichier en mode auto ; les éléments d'un tableau massif sont lus un par un.
+    fn auto_values(
+        path: &Path,
+        opts: &ParseOptions,
+    ) -> Result<Box<dyn Iterator<Item = Result<Value, ParseError>> + Send>, ParseError> {
         let metadata = std::fs::metadata(path)?;
-        if metadata.len() < 512 * 1024 * 1024 {
-            // < 512 Mo : charge tout en mémoire
-            Self::parse(path)
+        let values = if decompress::detect_file(path)? != Compression::None {
+            // Compressé : la taille sur disque ne dit rien, décompression complète en mémoire
+            Self::parse(path)?
+        } else if metadata.len() < opts.in_memory_threshold {
+            // Sous le seuil (512 Mo par défaut) : charge tout en mémoire
+            Self::parse(path)?
+        } else if Self::first_byte(path)? == Some(b'[') {
+            // Tableau massif : un élément en mémoire à la fois
+            return Ok(Box::new(Self::iter_array(path, "$")?));
         } else {
-            // > 512 Mo : tente JSONL mmap+rayon, sinon streaming
-            match Self::parse_jsonl_parallel(path) {
-                Ok(v) if !v.is_empty() => Ok(v),
-                _ => Ok(vec![Self::parse_streaming::<Value>(path)?]),
+            // Sinon : tente JSONL mmap+rayon, puis streaming
+            match opts.install(|| Self::parse_jsonl_parallel(path)) {
+                Ok(v) if !v.is_empty() => v,
+                _ => vec![Self::parse_streaming::<Value>(path)?],
             }
+        };
+        Ok(Box::new(values.into_iter().map(Ok)))
+    }
+
+    /// Ouvre un fichier JSONL en [`JsonlDocument`] zero-copy, syntaxe vérifiée en parallèle.
+    pub fn open_jsonl(path: &Path) -> Result<JsonlDocument, ParseError> {
+        Self::open_jsonl_with(path, &ParseOptions::default())
+    }
+
+    /// Comme [`JsonParser::open_jsonl`], avec les options de `opts` (validation UTF-8, pool…).
+    pub fn open_jsonl_with(path: &Path, opts: &ParseOptions) -> Result<JsonlDocument, ParseError> {
+        match Self::jsonl_document(decompress::open_data(path)?, opts, Some(path))? {
+            Ok(jsonl) => Ok(jsonl),
+            Err(_) => Err(ParseError::Format(format!(
+                "{}: not JSON Lines, the first record spans several lines",
+                path.display()
+            ))),
         }
     }
+
+    /// Plages des éléments du tableau JSON `data`, délimités en parallèle par chunks
+    /// d'environ `chunk_size` octets (seule la structure est vérifiée).
+    pub fn index_array(data: &[u8], chunk_size: usize) -> Result<LineIndex, ParseError> {
+        array_index::index_array(data, 0, chunk_size)
+    }
+
+    /// Document d'un tableau JSON à la racine : la ligne `i` est le texte brut de
+    /// l'élément `i`, pris dans le mmap sans conversion en JSONL.
+    pub fn parse_array(path: &Path) -> Result<Document, ParseError> {
+        Self::parse_array_with(path, &ParseOptions::default())
+    }
+
+    /// Comme [`JsonParser::parse_array`], avec les options de `opts`.
+    pub fn parse_array_with(path: &Path, opts: &ParseOptions) -> Result<Document, ParseError> {
+        Self::array_document(decompress::open_data(path)?, opts)
+            .map(|doc| doc.with_path(path))
+            .map_err(|e| e.with_path(path))
+    }
+
+    /// Indexe les éléments en parallèle puis vérifie leur syntaxe.
+    fn array_document(data: DocumentData, opts: &ParseOptions) -> Result<Document, ParseError> {
+        opts.madvise.apply(&data);
+        let mut scan = Ok(());
+        let doc = Document::from_data_with(data, opts, |bytes, bom| {
+            array_index::index_array(bytes, bom, opts.chunk_size).unwrap_or_else(|e| {
+                scan = Err(e);
+                LineIndex::new()
+            })
+        })?;
+        scan?;
+        array_index::check_elements(doc.data.as_bytes(), &doc.offsets, opts)?;
+        Ok(doc)
+    }
+
+    /// Vrai si `data` commence par `[` (BOM et blancs ignorés).
+    fn starts_with_array(data: &[u8]) -> bool {
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        data.trim_ascii_start().first() == Some(&b'[')
+    }
+
+    /// Indexe `data` en JSONL ; rend le Document brut si ce n'est pas du JSONL.
+    fn jsonl_document(
+        data: DocumentData,
+        opts: &ParseOptions,
+        path: Option<&Path>,
+    ) -> Result<Result<JsonlDocument, parser_core::Document>, ParseError> {
+        opts.madvise.apply(&data);
+        let mut jsonl = JsonlDocument::index(data, opts);
+        if let Some(path) = path {
+            jsonl = jsonl
+                .map(|j| j.with_path(path))
+                .map_err(|e| e.with_path(path));
+        }
+        let jsonl = jsonl?;
+        Ok(if jsonl.check(opts)? {
+            Ok(jsonl)
+        } else {
+            Err(jsonl.into_document())
+        })
+    }
+
     /// Retourne un iterator sur les objets JSON du fichier (JSONL ou tableau).
     pub fn iter_objects(path: &Path) -> Result<JsonObjectIter, ParseError> {
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mut first_bytes = [0u8; 16];
-        let n = reader.read(&mut first_bytes)?;
-        let first = std::str::from_utf8(&first_bytes[..n])?.trim_start();
-        if first.starts_with('[') {
-            // Streaming sur tableau JSON
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-            let deser = serde_json::Deserializer::from_reader(reader);
-            let iter = deser
-                .into_iter::<Value>()
-                .map(|v| v.map_err(|e| ParseError::Format(e.to_string())));
-            Ok(JsonObjectIter::Array(Box::new(iter)))
+        Self::iter_reader(decompress::decompress_reader(File::open(path)?)?)
+    }
+
+    /// Iterator sur les éléments du tableau situé à `json_path` (`$` pour la racine,
+    /// `$.data.items` pour un tableau imbriqué), lus un par un en mémoire bornée.
+    pub fn iter_array(path: &Path, json_path: &str) -> Result<JsonObjectIter, ParseError> {
+        let reader = BufReader::new(decompress::decompress_reader(File::open(path)?)?);
+        let stream = ArrayStream::<_, Value>::new(reader, json_path)?;
+        let path = path.to_path_buf();
+        Ok(JsonObjectIter::Array(Box::new(
+            stream.map(move |v| v.map_err(|e| e.with_path(&path))),
+        )))
+    }
+
+    /// Iterator sur les objets JSON lus depuis un flux (stdin, pipe, socket…).
+    ///
+    /// Le format est détecté sur le premier octet significatif : `[` pour un tableau
+    /// (éléments lus un par un), sinon JSONL (une valeur par ligne, lue ligne à ligne).
+    pub fn iter_reader<R: Read + Send + 'static>(reader: R) -> Result<JsonObjectIter, ParseError> {
+        let mut reader = BufReader::new(reader);
+        // Saute les blancs de tête sans rien consommer d'autre.
+        let mut pos = Position::default();
+        let first = skip_whitespace(&mut reader, &mut pos)?;
+        if first == Some(b'[') {
+            let stream = ArrayStream::<_, Value>::resume(reader, Vec::new(), "$", pos);
+            Ok(JsonObjectIter::Array(Box::new(stream)))
         } else {
-            // JSONL : une ligne = un objet JSON
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-            let iter = reader.lines().filter_map(|l| match l {
-                Ok(line) if !line.trim().is_empty() => Some(
-                    serde_json::from_str::<Value>(&line)
-                        .map_err(|e| ParseError::Format(e.to_string())),
-                ),
-                _ => None,
+            // JSONL : une ligne = un objet JSON, offsets suivis pour localiser les erreurs
+            let mut buf = Vec::new();
+            let mut idx = pos.line;
+            let mut offset = pos.offset;
+            let iter = std::iter::from_fn(move || loop {
+                buf.clear();
+                let n = match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) => return None,
+                    Ok(n) => n,
+                    Err(e) => return Some(Err(ParseError::Io(e))),
+                };
+                let (line_idx, line_start) = (idx, offset);
+                idx += 1;
+                offset += n as u64;
+                let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                return Some(
+                    serde_json::from_slice::<Value>(line)
+                        .map_err(|e| json_error(line, line_idx, line_start, e)),
+                );
             });
             Ok(JsonObjectIter::Jsonl(Box::new(iter)))
         }
@@ -138,17 +304,14 @@ impl JsonParser {
 
     /// Détecte automatiquement si le fichier est JSONL (une ligne = un objet) ou JSON standard (objet/tableau).
     fn detect_jsonl(path: &Path) -> Result<bool, ParseError> {
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mut buf = [0u8; 32];
-        let n = reader.read(&mut buf)?;
-        let s = std::str::from_utf8(&buf[..n])?.trim_start();
         // Si ça commence par [ ou {, c'est du JSON standard
-        if s.starts_with('[') || s.starts_with('{') {
-            Ok(false)
-        } else {
-            Ok(true)
-        }
+        Ok(!matches!(Self::first_byte(path)?, Some(b'[' | b'{')))
+    }
+
+    /// Premier octet significatif du fichier (décompressé), blancs de tête sautés.
+    fn first_byte(path: &Path) -> Result<Option<u8>, ParseError> {
+        let mut reader = BufReader::new(decompress::decompress_reader(File::open(path)?)?);
+        Ok(skip_whitespace(&mut reader, &mut Position::default())?)
     }
 
     /// Parse un fichier JSON en mode auto, simd, ou streaming, ou JSONL parallèle.
@@ -169,69 +332,235 @@ impl JsonParser {
 
     /// Parsing JSONL en parallèle avec simd-json.
     pub fn parse_jsonl_parallel_simd(path: &Path) -> Result<Vec<Value>, ParseError> {
-        let file = File::open(path)?;
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let data = &mmap[..];
-        let text = std::str::from_utf8(data)?;
-        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
-        let values: Result<Vec<_>, _> = lines
+        Self::parse_jsonl_parallel_typed(path)
+    }
+
+    /// Parsing JSONL en parallèle avec simd-json, chaque ligne désérialisée directement
+    /// en `T` (structure utilisateur, `Value`, [`OwnedValue`]…).
+    pub fn parse_jsonl_parallel_typed<T: DeserializeOwned + Send>(
+        path: &Path,
+    ) -> Result<Vec<T>, ParseError> {
+        let backing = decompress::open_data(path)?;
+        let text = utf8_text(backing.as_bytes()).map_err(|e| e.with_path(path))?;
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .collect();
+        lines
             .par_iter()
-            .map(|line| {
-                let mut line_bytes = line.as_bytes().to_vec();
-                simd_json::to_owned_value(&mut line_bytes)
-                    .map(|v| serde_json::to_value(v).map_err(|e| ParseError::Format(e.to_string())))
-                    .unwrap_or_else(|_| {
-                        serde_json::from_str(line).map_err(|e| ParseError::Format(e.to_string()))
-                    })
+            .map_init(LineScratch::default, |scratch, &(idx, line)| {
+                scratch
+                    .parse(line)
+                    .map_err(|e| line_error(text, line, idx, e).with_path(path))
             })
-            .collect();
-        values.map(|v| v.into_iter().collect())
+            .collect()
     }
 
     /// Parsing JSON standard (objet/tableau) avec simd-json.
     pub fn parse_simd(path: &Path) -> Result<Vec<Value>, ParseError> {
-        let data = std::fs::read(path)?;
-        let mut data_mut = data.clone();
-        match simd_json::to_owned_value(&mut data_mut) {
-            Ok(v) => Ok(vec![
-                serde_json::to_value(v).map_err(|e| ParseError::Format(e.to_string()))?
-            ]),
-            Err(_) => Ok(vec![
-                serde_json::from_slice(&data).map_err(|e| ParseError::Format(e.to_string()))?
-            ]),
+        Ok(vec![Self::parse_simd_typed(path)?])
+    }
+
+    /// Parsing JSON standard avec simd-json, directement en `T`, sur le buffer du
+    /// fichier (aucune copie). L'erreur est celle de simd-json, localisée à l'octet.
+    pub fn parse_simd_typed<T: DeserializeOwned>(path: &Path) -> Result<T, ParseError> {
+        let mut data = decompress::read_file(path)?;
+        simd_json::serde::from_slice(&mut data).map_err(|e| simd_error(e).with_path(path))
+    }
+}
+
+/// Tampons simd-json réutilisés d'une ligne à l'autre (simd-json réécrit son entrée).
+#[derive(Default)]
+struct LineScratch {
+    line: Vec<u8>,
+    buffers: simd_json::Buffers,
+}
+
+impl LineScratch {
+    /// Désérialise `line` avec simd-json ; serde_json en repli, pour une erreur localisée.
+    fn parse<T: DeserializeOwned>(&mut self, line: &str) -> Result<T, serde_json::Error> {
+        self.line.clear();
+        self.line.extend_from_slice(line.as_bytes());
+        simd_json::serde::from_slice_with_buffers(&mut self.line, &mut self.buffers)
+            .or_else(|_| serde_json::from_str(line))
+    }
+}
+
+/// Vérifie l'UTF-8 d'un buffer, erreur localisée à l'octet fautif.
+fn utf8_text(data: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(data).map_err(|e| ParseError::utf8_in_buffer(data, e))
+}
+
+/// Catégorie parser-core d'une erreur serde_json.
+fn json_kind(e: &serde_json::Error) -> JsonErrorKind {
+    match e.classify() {
+        Category::Io => JsonErrorKind::Io,
+        Category::Syntax => JsonErrorKind::Syntax,
+        Category::Data => JsonErrorKind::Data,
+        Category::Eof => JsonErrorKind::Eof,
+    }
+}
+
+/// Erreur simd-json : seul l'offset est fiable, le buffer ayant été réécrit.
+fn simd_error(e: simd_json::Error) -> ParseError {
+    let (kind, message, offset) = match e.error() {
+        // Erreur de désérialisation vers `T` : pas de position
+        ErrorType::Serde(msg) => (JsonErrorKind::Data, msg.clone(), None),
+        t if e.is_io() => (JsonErrorKind::Io, format!("{t:?}"), None),
+        t if e.is_eof() => (JsonErrorKind::Eof, format!("{t:?}"), Some(e.index() as u64)),
+        t if e.is_syntax() => (
+            JsonErrorKind::Syntax,
+            format!("{t:?}"),
+            Some(e.index() as u64),
+        ),
+        t => (
+            JsonErrorKind::Data,
+            format!("{t:?}"),
+            Some(e.index() as u64),
+        ),
+    };
+    ParseError::Json {
+        kind,
+        message,
+        location: Box::new(Location {
+            byte_offset: offset,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Message serde_json sans le suffixe " at line L column C" (repris par la localisation).
+fn json_message(e: &serde_json::Error) -> String {
+    let mut msg = e.to_string();
+    if e.line() > 0 {
+        if let Some(p) = msg.rfind(" at line ") {
+            msg.truncate(p);
         }
     }
+    msg
 }
 
-impl DocumentParser for JsonParser {
-    fn parse(path: &Path) -> Result<parser_core::Document, ParseError> {
-        let mut values = Self::parse_auto(path)?;
+/// Erreur serde_json sur `data`, dont la première ligne est la ligne `first_line`
+/// du fichier et le premier octet l'offset `base`.
+fn json_error(data: &[u8], first_line: usize, base: u64, e: serde_json::Error) -> ParseError {
+    let location = if e.line() == 0 {
+        Location::default()
+    } else {
+        let start = if e.line() == 1 {
+            0
+        } else {
+            memchr_iter(b'\n', data)
+                .nth(e.line() - 2)
+                .map_or(data.len(), |p| p + 1)
+        };
+        let end = memchr(b'\n', &data[start..]).map_or(data.len(), |p| start + p);
+        let col = e.column().saturating_sub(1).min(end - start);
+        Location::in_line(
+            &data[start..end],
+            first_line + e.line() - 1,
+            base + start as u64,
+            base + (start + col) as u64,
+        )
+    };
+    ParseError::Json {
+        kind: json_kind(&e),
+        message: json_message(&e),
+        location: Box::new(location),
+    }
+}
+
+/// Erreur serde_json sur la ligne `idx` de `text`, `line` étant une sous-tranche de `text`.
+fn line_error(text: &str, line: &str, idx: usize, e: serde_json::Error) -> ParseError {
+    let base = line.as_ptr() as usize - text.as_ptr() as usize;
+    json_error(line.as_bytes(), idx, base as u64, e)
+}
+
+/// Erreur serde_json d'un flux : seule la ligne/colonne est connue.
+fn stream_error(e: serde_json::Error) -> ParseError {
+    ParseError::Json {
+        kind: json_kind(&e),
+        message: json_message(&e),
+        location: Box::new(Location::at_line_column(e.line(), e.column())),
+    }
+}
+
+impl JsonParser {
+    /// Sérialise chaque valeur sur une "ligne" d'un Document en buffer.
+    ///
+    /// Les valeurs sont consommées au fil de l'eau : un tableau lu en flux n'est jamais
+    /// matérialisé en entier.
+    fn values_to_document<I>(values: I) -> Result<parser_core::Document, ParseError>
+    where
+        I: IntoIterator<Item = Result<Value, ParseError>>,
+    {
+        let mut values = values.into_iter();
+        let first = values.next().transpose()?;
+        let second = values.next().transpose()?;
         // Si le fichier est un tableau JSON unique, on découpe chaque élément comme une ligne
-        if values.len() == 1 {
-            if let Value::Array(arr) = &values[0] {
-                values = arr.clone();
-            }
-        }
+        let head = match (first, second) {
+            (Some(Value::Array(arr)), None) => arr,
+            (first, second) => first.into_iter().chain(second).collect(),
+        };
         // Sérialise chaque objet en texte (une "ligne" par objet)
         let mut buffer = Vec::new();
-        let mut offsets = Vec::with_capacity(values.len());
-        let mut pos = 0u32;
-        for v in values {
-            let s = serde_json::to_string(&v).map_err(|e| ParseError::Format(e.to_string()))?;
-            let bytes = s.as_bytes();
-            let len = bytes.len() as u32;
-            buffer.extend_from_slice(bytes);
+        let mut offsets = parser_core::LineIndex::with_capacity(0, head.len());
+        for v in head.into_iter().map(Ok).chain(values) {
+            let start = buffer.len() as u64;
+            serde_json::to_writer(&mut buffer, &v?)
+                .map_err(|e| ParseError::Format(e.to_string()))?;
+            offsets.push(start, buffer.len() as u64 - start);
             buffer.push(b'\n');
-            offsets.push((pos, len));
-            pos += len + 1;
         }
-        Ok(parser_core::Document {
-            data: parser_core::DocumentData::Buffer(std::sync::Arc::new(buffer)),
-            offsets,
+        // Safety: chaque ligne est un JSON sérialisé par serde_json, donc UTF-8.
+        Ok(unsafe {
+            parser_core::Document::new_unchecked(
+                parser_core::DocumentData::Buffer(std::sync::Arc::new(buffer)),
+                offsets,
+            )
         })
     }
 }
 
+impl DocumentParser for JsonParser {
+    const INDEX_KIND: u8 = b'j';
+
+    fn parse_with(path: &Path, opts: &ParseOptions) -> Result<parser_core::Document, ParseError> {
+        let data = decompress::open_data(path)?;
+        if Self::starts_with_array(data.as_bytes()) {
+            // Tableau : éléments délimités en parallèle, pris tels quels dans le mmap
+            return Self::array_document(data, opts)
+                .map(|doc| doc.with_path(path))
+                .map_err(|e| e.with_path(path));
+        }
+        // JSONL : lignes prises telles quelles dans le mmap, sans re-sérialisation
+        match Self::jsonl_document(data, opts, Some(path))? {
+            Ok(jsonl) => Ok(jsonl.into_document()),
+            // JSON indenté sur plusieurs lignes : un seul document
+            Err(doc) => Self::values_to_document(std::iter::once(
+                Self::parse_whole(doc.data.as_bytes()).map_err(|e| e.with_path(path)),
+            )),
+        }
+    }
+
+    fn parse_bytes_with(
+        data: Vec<u8>,
+        opts: &ParseOptions,
+    ) -> Result<parser_core::Document, ParseError> {
+        let array = Self::starts_with_array(&data);
+        let data = DocumentData::Buffer(std::sync::Arc::new(data));
+        if array {
+            return Self::array_document(data, opts);
+        }
+        match Self::jsonl_document(data, opts, None)? {
+            Ok(jsonl) => Ok(jsonl.into_document()),
+            Err(doc) => {
+                Self::values_to_document(std::iter::once(Self::parse_whole(doc.data.as_bytes())))
+            }
+        }
+    }
+}
+
 impl JsonParser {
     /// API harmonisée : parse et retourne un Document (compatibilité txt-parser)
     pub fn parse_as_document(path: &Path) -> Result<parser_core::Document, ParseError> {
@@ -0,0 +1,180 @@
+//! Mode JSONL tolérant : les lignes invalides sont écartées et signalées au lieu de
+//! faire échouer tout le parsing.
+//!
+//! Un budget d'erreurs borne le nombre de rejets acceptés ; au-delà, le parsing
+//! s'arrête au plus tôt. Les lignes rejetées peuvent être recopiées telles quelles
+//! dans un fichier de quarantaine, pour correction puis rejeu.
+
+use crate::{json_error, JsonParser, LineScratch};
+use parser_core::chunks;
+use parser_core::decompress;
+use parser_core::options::Validation;
+use parser_core::{Document, Location, ParseError, ParseOptions};
+use rayon::prelude::*;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Options du mode tolérant.
+#[derive(Debug, Clone, Default)]
+pub struct TolerantOptions {
+    /// Nombre maximal de lignes rejetées ; `None` : illimité.
+    pub max_errors: Option<usize>,
+    /// Fichier recevant les lignes rejetées, brutes, dans l'ordre du fichier.
+    pub quarantine: Option<PathBuf>,
+}
+
+impl TolerantOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_errors(mut self, max: usize) -> Self {
+        self.max_errors = Some(max);
+        self
+    }
+
+    pub fn quarantine(mut self, path: impl Into<PathBuf>) -> Self {
+        self.quarantine = Some(path.into());
+        self
+    }
+}
+
+/// Ligne rejetée.
+#[derive(Debug)]
+pub struct Rejected {
+    /// Ligne du fichier (base 0, comme [`parser_core::Location`]).
+    pub line: usize,
+    /// Offset du début de la ligne, dans les données décompressées ; pour une entrée
+    /// transcodée (UTF-16, Latin-1…), dans le texte converti en UTF-8.
+    pub byte_offset: u64,
+    pub error: ParseError,
+    /// Extrait de la ligne autour de l'erreur.
+    pub snippet: String,
+}
+
+/// Enregistrements valides et lignes rejetées, dans l'ordre du fichier.
+#[derive(Debug)]
+pub struct TolerantJsonl<T> {
+    pub records: Vec<T>,
+    pub rejected: Vec<Rejected>,
+}
+
+impl JsonParser {
+    /// Parse un fichier JSONL en parallèle en écartant les lignes invalides (JSON,
+    /// type, UTF-8 ou trop longues) au lieu d'échouer.
+    ///
+    /// Échoue seulement sur erreur d'E/S ou si le budget `max_errors` est dépassé.
+    pub fn parse_jsonl_tolerant<T: DeserializeOwned + Send>(
+        path: &Path,
+        tolerant: &TolerantOptions,
+    ) -> Result<TolerantJsonl<T>, ParseError> {
+        Self::parse_jsonl_tolerant_with(path, tolerant, &ParseOptions::default())
+    }
+
+    /// Comme [`JsonParser::parse_jsonl_tolerant`], avec les options de `opts` (encodage
+    /// et BOM, taille de chunk, terminateur, pool, madvise).
+    ///
+    /// La validation de `opts` est sans effet : une ligne non UTF-8 est rejetée ; de
+    /// même, une ligne plus longue que `opts.max_line_length` compte dans le budget
+    /// d'erreurs au lieu de faire échouer le parsing. Les offsets et extraits d'une
+    /// entrée transcodée se rapportent au texte converti en UTF-8.
+    pub fn parse_jsonl_tolerant_with<T: DeserializeOwned + Send>(
+        path: &Path,
+        tolerant: &TolerantOptions,
+        opts: &ParseOptions,
+    ) -> Result<TolerantJsonl<T>, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        let mut lenient = opts.clone().validation(Validation::Off);
+        // Longueur vérifiée ligne par ligne ci-dessous, comme une erreur de la ligne.
+        lenient.max_line_length = None;
+        let doc = Document::from_data_with(backing, &lenient, |bytes, bom| {
+            chunks::index_lines_from(bytes, bom, opts.chunk_size, opts.line_terminator)
+        })
+        .map_err(|e| e.with_path(path))?;
+        let data = doc.data.as_bytes();
+        let lines = &doc.offsets;
+        let budget = tolerant.max_errors.unwrap_or(usize::MAX);
+        let errors = AtomicUsize::new(0);
+        let line = |i: usize| {
+            let (start, len) = lines.get(i).unwrap();
+            (start, &data[start as usize..(start + len) as usize])
+        };
+        let results: Vec<(usize, Result<T, ParseError>)> = opts.install(|| {
+            (0..lines.len())
+                .into_par_iter()
+                .with_min_len(1024)
+                .map_init(LineScratch::default, |scratch, i| {
+                    let (start, bytes) = line(i);
+                    if bytes.trim_ascii().is_empty() {
+                        return Some(None);
+                    }
+                    let result = match opts.max_line_length {
+                        Some(max) if bytes.len() > max => Err(ParseError::InvalidLine {
+                            location: Box::new(Location::in_line(
+                                bytes,
+                                i,
+                                start,
+                                start + max as u64,
+                            )),
+                            message: format!("line too long: {} bytes (max {max})", bytes.len()),
+                        }),
+                        _ => std::str::from_utf8(bytes)
+                            .map_err(|e| ParseError::utf8_in_line(bytes, i, start, e)),
+                    }
+                    .and_then(|text| {
+                        scratch
+                            .parse(text)
+                            .map_err(|e| json_error(bytes, i, start, e))
+                    });
+                    // Budget épuisé : arrêt au plus tôt
+                    if result.is_err() && errors.fetch_add(1, Ordering::Relaxed) >= budget {
+                        return None;
+                    }
+                    Some(Some((i, result)))
+                })
+                .while_some()
+                .flatten()
+                .collect()
+        });
+        let errors = errors.into_inner();
+        if errors > budget {
+            return Err(ParseError::Format(format!(
+                "{}: error budget exceeded, more than {budget} malformed lines",
+                path.display()
+            )));
+        }
+
+        let mut records = Vec::with_capacity(results.len() - errors);
+        let mut rejected = Vec::with_capacity(errors);
+        for (i, result) in results {
+            match result {
+                Ok(v) => records.push(v),
+                Err(e) => {
+                    let error = e.with_path(path);
+                    rejected.push(Rejected {
+                        line: i,
+                        byte_offset: line(i).0,
+                        snippet: error
+                            .location()
+                            .and_then(|l| l.snippet.clone())
+                            .unwrap_or_default(),
+                        error,
+                    });
+                }
+            }
+        }
+        if let Some(quarantine) = &tolerant.quarantine {
+            let mut out = BufWriter::new(File::create(quarantine)?);
+            for r in &rejected {
+                out.write_all(line(r.line).1)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()?;
+        }
+        Ok(TolerantJsonl { records, rejected })
+    }
+}
@@ -1,5 +1,5 @@
 use json_parser::JsonParser;
-use parser_core::Document;
+use parser_core::{Document, DocumentParser, JsonErrorKind, ParseError, ParseOptions};
 use serde_json::json;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -75,9 +75,470 @@ fn test_parse_jsonl_malformed() {
     assert!(res.is_err());
 }
 
+#[test]
+fn test_jsonl_error_location() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{{\"a\":1}}\n{{\"a\":2}}\n{{\"a\":3,}}\n").unwrap();
+    for res in [
+        JsonParser::parse_jsonl_parallel(file.path()),
+        JsonParser::parse_jsonl_parallel_simd(file.path()),
+        JsonParser::parse_mode(file.path(), Some("jsonl")),
+    ] {
+        let err = res.unwrap_err();
+        let ParseError::Json { location, kind, .. } = &err else {
+            panic!("erreur JSON attendue : {err}");
+        };
+        assert_eq!(*kind, JsonErrorKind::Syntax);
+        assert_eq!(location.path.as_deref(), Some(file.path()));
+        assert_eq!(location.line, Some(2));
+        assert_eq!(location.byte_offset, Some(23));
+        assert!(err.to_string().contains("{\"a\":3,}"));
+    }
+
+    let iter =
+        JsonParser::iter_reader(std::io::Cursor::new(std::fs::read(file.path()).unwrap())).unwrap();
+    let err = iter.filter_map(Result::err).next().unwrap();
+    assert_eq!(err.location().unwrap().line, Some(2));
+    assert_eq!(err.location().unwrap().byte_offset, Some(23));
+}
+
+#[test]
+fn test_json_document_error_location() {
+    let err = JsonParser::parse_slice(b"{\"a\": [1, 2, }").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!(loc.line, Some(0));
+    assert_eq!(loc.column, Some(14));
+}
+
 #[test]
 fn test_parse_json_file_not_found() {
     let path = std::path::Path::new("/tmp/__fichier_inexistant__.json");
     let res = JsonParser::parse(path);
     assert!(res.is_err());
 }
+
+#[test]
+fn test_iter_reader_and_parse_reader() {
+    let jsonl = std::io::Cursor::new(b"{\"a\":1}\n\n{\"b\":2}\n".to_vec());
+    let values: Vec<_> = JsonParser::iter_reader(jsonl)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(values, vec![json!({"a": 1}), json!({"b": 2})]);
+
+    let array = std::io::Cursor::new(b"  \n [{\"a\":1},{\"b\":2}]".to_vec());
+    let values: Vec<_> = JsonParser::iter_reader(array)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(values, vec![json!({"a": 1}), json!({"b": 2})]);
+
+    let doc = JsonParser::parse_reader(&b"[{\"a\":1},{\"b\":2}]"[..]).unwrap();
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["{\"a\":1}", "{\"b\":2}"]
+    );
+}
+
+#[test]
+fn test_parse_gzip_jsonl_transparently() {
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(b"{\"a\":1}\n{\"b\":2}\n").unwrap();
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(&enc.finish().unwrap()).unwrap();
+
+    let doc = JsonParser::parse_as_document(file.path()).unwrap();
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["{\"a\":1}", "{\"b\":2}"]
+    );
+    let values: Vec<_> = JsonParser::iter_objects(file.path())
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(values, vec![json!({"a": 1}), json!({"b": 2})]);
+    assert_eq!(
+        JsonParser::parse_mode(file.path(), Some("jsonl")).unwrap(),
+        vec![json!({"a": 1}), json!({"b": 2})]
+    );
+}
+
+#[test]
+fn test_iter_array_streams_elements() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "[\n  {{\"s\": \"a,]}}\\\"\"}},\n  [1, [2]],\n  -3.5e2, true, null, \"x\"\n]\ntrailing"
+    )
+    .unwrap();
+    let values: Vec<_> = JsonParser::iter_array(file.path(), "$")
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            json!({"s": "a,]}\""}),
+            json!([1, [2]]),
+            json!(-350.0),
+            json!(true),
+            json!(null),
+            json!("x")
+        ]
+    );
+
+    let mut empty = NamedTempFile::new().unwrap();
+    write!(empty, " [ ] ").unwrap();
+    assert_eq!(
+        JsonParser::iter_array(empty.path(), "$").unwrap().count(),
+        0
+    );
+}
+
+#[test]
+fn test_iter_array_nested_path() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        r#"{{"meta": {{"items": [0]}}, "data": {{"count": 2, "items": [{{"id": 1}}, {{"id": 2}}]}}}}"#
+    )
+    .unwrap();
+    let values: Vec<_> = JsonParser::iter_array(file.path(), "$.data.items")
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(values, vec![json!({"id": 1}), json!({"id": 2})]);
+
+    let err = JsonParser::iter_array(file.path(), "$.data.missing")
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert!(err.to_string().contains("$.data.missing not found"));
+    let err = JsonParser::iter_array(file.path(), "$.data.count")
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert!(err.to_string().contains("expected an array"));
+    assert!(JsonParser::iter_array(file.path(), "$..items").is_err());
+}
+
+#[test]
+fn test_iter_array_error_location() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "[\n  {{\"a\": 1}},\n  {{\"b\": tru}}\n]").unwrap();
+    let mut it = JsonParser::iter_array(file.path(), "$").unwrap();
+    assert_eq!(it.next().unwrap().unwrap(), json!({"a": 1}));
+    let err = it.next().unwrap().unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!(loc.path.as_deref(), Some(file.path()));
+    assert_eq!(loc.line, Some(2));
+    assert_eq!(loc.column, Some(12));
+    assert!(matches!(
+        err,
+        ParseError::Json {
+            kind: JsonErrorKind::Syntax,
+            ..
+        }
+    ));
+    // L'itération s'arrête à la première erreur
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn test_parse_auto_streams_large_array() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "[{{\"a\":1}},\n{{\"b\":2}}]").unwrap();
+    let opts = ParseOptions::new().in_memory_threshold(0);
+    assert_eq!(
+        JsonParser::parse_auto_with(file.path(), &opts).unwrap(),
+        vec![json!({"a": 1}), json!({"b": 2})]
+    );
+    let doc = JsonParser::parse_with(file.path(), &opts).unwrap();
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["{\"a\":1}", "{\"b\":2}"]
+    );
+}
+
+#[test]
+fn test_jsonl_document_zero_copy() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{{\"a\": 1}}\n\n[2, \"x\"]\r\n  \n{{\"c\":  3}}").unwrap();
+    // Les lignes gardent leur forme d'origine (pas de re-sérialisation)
+    let doc = JsonParser::parse_as_document(file.path()).unwrap();
+    assert!(matches!(doc.data, parser_core::DocumentData::Mmap(_)));
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["{\"a\": 1}", "[2, \"x\"]", "{\"c\":  3}"]
+    );
+
+    let jsonl = JsonParser::open_jsonl(file.path()).unwrap();
+    assert_eq!(jsonl.len(), 3);
+    assert_eq!(jsonl.raw(2).unwrap(), "{\"c\":  3}");
+    assert_eq!(jsonl.value(0).unwrap(), json!({"a": 1}));
+    let (n, s): (u32, &str) = jsonl.get(1).unwrap();
+    assert_eq!((n, s), (2, "x"));
+    let values: Vec<serde_json::Value> = jsonl.records().map(Result::unwrap).collect();
+    assert_eq!(values[2], json!({"c": 3}));
+    // Erreur de type localisée sur la ligne du fichier, lignes vides comprises
+    let err = jsonl.get::<Vec<u32>>(2).unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!(loc.line, Some(4));
+    assert_eq!(loc.path.as_deref(), Some(file.path()));
+}
+
+#[test]
+fn test_jsonl_document_syntax_error() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{{\"a\":1}}\n\n{{\"a\":2}}\n{{\"a\":3,}}\n").unwrap();
+    for err in [
+        JsonParser::open_jsonl(file.path()).err().unwrap(),
+        JsonParser::parse_as_document(file.path()).err().unwrap(),
+    ] {
+        let loc = err.location().unwrap();
+        assert_eq!(loc.line, Some(3));
+        assert_eq!(loc.byte_offset, Some(24));
+        assert_eq!(loc.path.as_deref(), Some(file.path()));
+    }
+
+    // JSON indenté : pas du JSONL, repli sur le parsing complet
+    let mut pretty = NamedTempFile::new().unwrap();
+    write!(pretty, "{{\n  \"a\": [1,\n 2]\n}}\n").unwrap();
+    assert!(JsonParser::open_jsonl(pretty.path()).is_err());
+    let doc = JsonParser::parse_as_document(pretty.path()).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["{\"a\":[1,2]}"]);
+    let doc = JsonParser::parse_bytes(std::fs::read(pretty.path()).unwrap()).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["{\"a\":[1,2]}"]);
+    assert_eq!(
+        JsonParser::parse_auto(pretty.path()).unwrap(),
+        vec![json!({"a": [1, 2]})]
+    );
+}
+
+#[test]
+fn test_index_array_chunk_independent() {
+    let data = br#" [ {"s": "a,]}\\\"\\", "t": [1, {"u": ","}]},
+  "x\\",
+  [[], {}], -1.5e3 ,null,"\"[" ,
+  {"deep": [[[",]"]]]}
+] "#;
+    let expected = vec![
+        r#"{"s": "a,]}\\\"\\", "t": [1, {"u": ","}]}"#,
+        r#""x\\""#,
+        "[[], {}]",
+        "-1.5e3",
+        "null",
+        r#""\"[""#,
+        r#"{"deep": [[[",]"]]]}"#,
+    ];
+    for chunk_size in 1..=40 {
+        let index = JsonParser::index_array(data, chunk_size).unwrap();
+        let elements: Vec<_> = index
+            .iter()
+            .map(|(s, l)| std::str::from_utf8(&data[s as usize..(s + l) as usize]).unwrap())
+            .collect();
+        assert_eq!(elements, expected, "chunk_size = {chunk_size}");
+    }
+    assert_eq!(JsonParser::index_array(b"[ ]", 1).unwrap().len(), 0);
+    assert!(JsonParser::index_array(b"[1, [2]", 2).is_err());
+    assert!(JsonParser::index_array(b"[1] x", 2).is_err());
+    assert!(JsonParser::index_array(b"{\"a\": 1}", 2).is_err());
+}
+
+#[test]
+fn test_parse_array_document() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "[\n  {{\"a\": 1,\n   \"b\": [2, 3]}},\n  \"x\"\n]\n").unwrap();
+    for doc in [
+        JsonParser::parse_array(file.path()).unwrap(),
+        JsonParser::parse_as_document(file.path()).unwrap(),
+    ] {
+        assert!(matches!(doc.data, parser_core::DocumentData::Mmap(_)));
+        assert_eq!(doc.line_count(), 2);
+        assert_eq!(doc.get_line(0).unwrap(), "{\"a\": 1,\n   \"b\": [2, 3]}");
+        assert_eq!(doc.get_line(1).unwrap(), "\"x\"");
+    }
+
+    let mut bad = NamedTempFile::new().unwrap();
+    write!(bad, "[\n  {{\"a\": 1}},\n  {{\"a\": 1 2}}\n]").unwrap();
+    let opts = ParseOptions::new().chunk_size(4);
+    let err = JsonParser::parse_array_with(bad.path(), &opts)
+        .err()
+        .unwrap();
+    let loc = err.location().unwrap();
+    assert_eq!(loc.path.as_deref(), Some(bad.path()));
+    assert_eq!(loc.line, Some(2));
+    assert_eq!(loc.column, Some(11));
+    assert_eq!(loc.byte_offset, Some(24));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Event {
+    id: u32,
+    name: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_parse_jsonl_typed() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(
+        file,
+        "{{\"id\": 1, \"name\": \"a\", \"tags\": [\"x\"]}}\n\n{{\"id\": 2, \"name\": \"b\\u00e9\"}}\n"
+    )
+    .unwrap();
+    let events: Vec<Event> = JsonParser::parse_jsonl_parallel_typed(file.path()).unwrap();
+    assert_eq!(
+        events,
+        vec![
+            Event {
+                id: 1,
+                name: "a".into(),
+                tags: vec!["x".into()]
+            },
+            Event {
+                id: 2,
+                name: "bé".into(),
+                tags: vec![]
+            },
+        ]
+    );
+    let simd: Vec<json_parser::OwnedValue> =
+        JsonParser::parse_jsonl_parallel_typed(file.path()).unwrap();
+    assert_eq!(simd.len(), 2);
+
+    // Champ manquant : erreur de données localisée sur la ligne du fichier
+    let mut bad = NamedTempFile::new().unwrap();
+    write!(bad, "{{\"id\": 1, \"name\": \"a\"}}\n{{\"id\": 2}}\n").unwrap();
+    let err = JsonParser::parse_jsonl_parallel_typed::<Event>(bad.path()).unwrap_err();
+    let ParseError::Json { kind, location, .. } = &err else {
+        panic!("erreur JSON attendue : {err}");
+    };
+    assert_eq!(*kind, JsonErrorKind::Data);
+    assert_eq!(location.line, Some(1));
+    assert_eq!(location.path.as_deref(), Some(bad.path()));
+}
+
+#[test]
+fn test_parse_simd_typed() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{{\"id\": 7, \"name\": \"n\"}}").unwrap();
+    let event: Event = JsonParser::parse_simd_typed(file.path()).unwrap();
+    assert_eq!(event.id, 7);
+    assert_eq!(
+        JsonParser::parse_simd(file.path()).unwrap(),
+        vec![json!({"id": 7, "name": "n"})]
+    );
+
+    let mut bad = NamedTempFile::new().unwrap();
+    write!(bad, "{{\"id\": 7,\n \"name\": }}").unwrap();
+    let err = JsonParser::parse_simd_typed::<Event>(bad.path()).unwrap_err();
+    let location = err.location().unwrap();
+    assert!(matches!(
+        err,
+        ParseError::Json {
+            kind: JsonErrorKind::Syntax,
+            ..
+        }
+    ));
+    assert_eq!(location.byte_offset, Some(19));
+    assert_eq!(location.path.as_deref(), Some(bad.path()));
+
+    let mut wrong = NamedTempFile::new().unwrap();
+    write!(wrong, "{{\"id\": \"x\", \"name\": \"n\"}}").unwrap();
+    let err = JsonParser::parse_simd_typed::<Event>(wrong.path()).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::Json {
+            kind: JsonErrorKind::Data,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_parse_jsonl_tolerant() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2,}\n\n{\"id\": \"x\", \"name\": \"b\"}\n{\"id\": 3, \"name\": \"\xff\"}\n{\"id\": 4, \"name\": \"d\"}\n")
+        .unwrap();
+    let quarantine = NamedTempFile::new().unwrap();
+    let opts = json_parser::TolerantOptions::new().quarantine(quarantine.path());
+    let out = JsonParser::parse_jsonl_tolerant::<Event>(file.path(), &opts).unwrap();
+    assert_eq!(
+        out.records.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![1, 4]
+    );
+    let lines: Vec<_> = out
+        .rejected
+        .iter()
+        .map(|r| (r.line, r.byte_offset))
+        .collect();
+    assert_eq!(lines, vec![(1, 23), (3, 35), (4, 60)]);
+    assert!(out.rejected[0].snippet.contains("{\"id\": 2,}"));
+    assert!(matches!(
+        out.rejected[2].error,
+        ParseError::InvalidUtf8 { .. }
+    ));
+    assert_eq!(
+        out.rejected[1].error.location().unwrap().path.as_deref(),
+        Some(file.path())
+    );
+    let rejects = std::fs::read(quarantine.path()).unwrap();
+    assert_eq!(
+        rejects,
+        b"{\"id\": 2,}\n{\"id\": \"x\", \"name\": \"b\"}\n{\"id\": 3, \"name\": \"\xff\"}\n"
+    );
+
+    // Budget d'erreurs dépassé : échec
+    let opts = json_parser::TolerantOptions::new().max_errors(2);
+    let err = JsonParser::parse_jsonl_tolerant::<Event>(file.path(), &opts).unwrap_err();
+    assert!(err.to_string().contains("more than 2 malformed lines"));
+    let opts = json_parser::TolerantOptions::new().max_errors(3);
+    assert_eq!(
+        JsonParser::parse_jsonl_tolerant::<Event>(file.path(), &opts)
+            .unwrap()
+            .rejected
+            .len(),
+        3
+    );
+
+    // Options de parsing : BOM retiré, `\r\n`, petits chunks, pool dédié
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"\xEF\xBB\xBF{\"id\": 1, \"name\": \"a\"}\r\n{\"id\": }\r\n{\"id\": 2, \"name\": \"b\"}\r\n")
+        .unwrap();
+    let parse_opts = ParseOptions::new().chunk_size(8).threads(2);
+    let out = JsonParser::parse_jsonl_tolerant_with::<Event>(
+        file.path(),
+        &json_parser::TolerantOptions::new(),
+        &parse_opts,
+    )
+    .unwrap();
+    assert_eq!(
+        out.records.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(out.rejected.len(), 1);
+    assert_eq!((out.rejected[0].line, out.rejected[0].byte_offset), (1, 27));
+
+    // Ligne trop longue : rejetée et mise en quarantaine, sans échec global
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": \"trop long\"}\n")
+        .unwrap();
+    let quarantine = NamedTempFile::new().unwrap();
+    let out = JsonParser::parse_jsonl_tolerant_with::<Event>(
+        file.path(),
+        &json_parser::TolerantOptions::new().quarantine(quarantine.path()),
+        &ParseOptions::new().max_line_length(24),
+    )
+    .unwrap();
+    assert_eq!(out.records.len(), 1);
+    assert_eq!(out.rejected.len(), 1);
+    assert!(out.rejected[0].error.to_string().contains("line too long"));
+    assert_eq!(
+        std::fs::read(quarantine.path()).unwrap(),
+        b"{\"id\": 2, \"name\": \"trop long\"}\n"
+    );
+}
@@ -0,0 +1,149 @@
+//! Découpage des données en chunks alignés sur les fins de ligne.
+//!
+//! Chaque chunk se termine juste après un `\n` (ou en fin de données), de sorte
+//! qu'aucune ligne ni aucun `\r\n` ne soit coupé entre deux chunks : un scan
+//! parallèle produit alors exactement le même index qu'un scan séquentiel.
+
+use crate::options::LineTerminator;
+use crate::LineIndex;
+use memchr::{memchr, memchr_iter};
+use rayon::prelude::*;
+
+/// Taille nominale des chunks pour le scan parallèle (64 MiB).
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Bornes [début, fin) de chunks d'environ `chunk_size` octets, alignées sur `\n`.
+///
+/// Un chunk peut dépasser `chunk_size` si une ligne est plus longue que lui.
+pub fn line_aligned_chunks(data: &[u8], chunk_size: usize) -> Vec<(usize, usize)> {
+    aligned_chunks(data, chunk_size, b'\n')
+}
+
+/// Comme [`line_aligned_chunks`], avec un terminateur arbitraire.
+pub fn aligned_chunks(data: &[u8], chunk_size: usize, terminator: u8) -> Vec<(usize, usize)> {
+    let len = data.len();
+    let chunk_size = chunk_size.max(1);
+    let mut bounds = Vec::with_capacity(len / chunk_size + 1);
+    let mut start = 0;
+    while start < len {
+        let nominal = start.saturating_add(chunk_size);
+        let end = if nominal >= len {
+            len
+        } else {
+            // Le chunk s'étend jusqu'au prochain terminateur inclus.
+            memchr(terminator, &data[nominal - 1..]).map_or(len, |p| nominal + p)
+        };
+        bounds.push((start, end));
+        start = end;
+    }
+    bounds
+}
+
+/// Indexe les lignes de `data` en parallèle, par chunks d'environ `chunk_size` octets.
+///
+/// Le résultat est identique à un scan séquentiel, quelle que soit la taille de chunk.
+pub fn index_lines(data: &[u8], chunk_size: usize, terminator: LineTerminator) -> LineIndex {
+    index_lines_from(data, 0, chunk_size, terminator)
+}
+
+/// Comme [`index_lines`], en ignorant les `from` premiers octets (BOM).
+pub fn index_lines_from(
+    data: &[u8],
+    from: usize,
+    chunk_size: usize,
+    terminator: LineTerminator,
+) -> LineIndex {
+    let len = data.len();
+    let byte = terminator.byte();
+    let strip_cr = terminator.strips_cr();
+    let sets: Vec<LineIndex> = aligned_chunks(&data[from..], chunk_size, byte)
+        .into_par_iter()
+        .map(|(s, e)| (s + from, e + from))
+        .map(|(s, e)| {
+            let slice = &data[s..e];
+            // u64 seulement si le fichier dépasse 4 GiB
+            let mut local = LineIndex::with_capacity(len as u64, slice.len() / 40 + 1);
+            let mut prev = 0;
+            for nl in memchr_iter(byte, slice) {
+                let line_end = if strip_cr && nl > prev && slice[nl - 1] == b'\r' {
+                    nl - 1
+                } else {
+                    nl
+                };
+                local.push((s + prev) as u64, (line_end - prev) as u64);
+                prev = nl + 1;
+            }
+            if prev < slice.len() {
+                local.push((s + prev) as u64, (slice.len() - prev) as u64);
+            }
+            local
+        })
+        .collect();
+
+    let mut offsets = LineIndex::with_capacity(len as u64, sets.iter().map(LineIndex::len).sum());
+    for v in sets {
+        offsets.append(v);
+    }
+    offsets
+}
+
+/// Vérifie l'UTF-8 de chaque ligne de `slice`, coupée sur `terminator`.
+///
+/// Un terminateur ASCII ne peut couper aucun caractère : la tranche est vérifiée d'un
+/// bloc. Un octet non ASCII (`Byte(0xA9)`…) peut tomber au milieu d'un caractère :
+/// chaque ligne est alors vérifiée séparément, exactement telle qu'elle sera lue.
+pub fn check_utf8_lines(
+    slice: &[u8],
+    terminator: LineTerminator,
+) -> Result<(), std::str::Utf8Error> {
+    let byte = terminator.byte();
+    if byte.is_ascii() {
+        return std::str::from_utf8(slice).map(drop);
+    }
+    slice
+        .split(|&b| b == byte)
+        .try_for_each(|line| std::str::from_utf8(line).map(drop))
+}
+
+/// Ligne commençant à `pos` : (longueur sans `\r\n`, position de la ligne suivante).
+///
+/// Même convention que les scans parallèles : `\r` final retiré, dernière ligne
+/// sans `\n` conservée.
+#[inline]
+pub fn line_at(data: &[u8], pos: usize) -> (usize, usize) {
+    line_at_with(data, pos, LineTerminator::Auto)
+}
+
+/// Comme [`line_at`], avec un terminateur arbitraire.
+#[inline]
+pub fn line_at_with(data: &[u8], pos: usize, terminator: LineTerminator) -> (usize, usize) {
+    match memchr(terminator.byte(), &data[pos..]) {
+        Some(p) => {
+            let end = pos + p;
+            let len = if terminator.strips_cr() && end > pos && data[end - 1] == b'\r' {
+                p - 1
+            } else {
+                p
+            };
+            (len, end + 1)
+        }
+        None => (data.len() - pos, data.len()),
+    }
+}
+
+/// Position de début de la `k`-ième ligne après celle qui commence à `pos`.
+#[inline]
+pub fn skip_lines(data: &[u8], pos: usize, k: usize) -> usize {
+    skip_lines_with(data, pos, k, LineTerminator::Auto)
+}
+
+/// Comme [`skip_lines`], avec un terminateur arbitraire.
+#[inline]
+pub fn skip_lines_with(data: &[u8], pos: usize, k: usize, terminator: LineTerminator) -> usize {
+    if k == 0 {
+        return pos;
+    }
+    memchr_iter(terminator.byte(), &data[pos..])
+        .nth(k - 1)
+        .map_or(data.len(), |p| pos + p + 1)
+}
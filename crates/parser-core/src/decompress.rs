@@ -0,0 +1,274 @@
+//! Décompression transparente des entrées (gzip, zstd, bzip2, xz).
+//!
+//! - Détection par magic bytes, indépendante de l'extension
+//! - Lecteur en flux pour stdin/pipes (mémoire bornée)
+//! - Décompression en buffer pour alimenter les parsers indexés
+//! - Parallèle (rayon) pour les fichiers multi-membres de taille annoncée : frames zstd
+//!   (taille de contenu), blocs BGZF
+
+use crate::{DocumentData, ParseError};
+use memmap2::MmapOptions;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Octets nécessaires pour reconnaître tous les formats.
+const MAGIC_LEN: usize = 10;
+
+/// Plafond de la pré-allocation du buffer décompressé (256 MiB) : le ratio réel
+/// est inconnu, le buffer grandit ensuite à la demande.
+const MAX_CAPACITY_HINT: usize = 256 * 1024 * 1024;
+
+/// Format de compression d'une entrée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    /// Détecte le format d'après les premiers octets.
+    pub fn detect(head: &[u8]) -> Self {
+        match head {
+            [0x1f, 0x8b, 0x08, ..] => Compression::Gzip,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            // "BZh" + niveau + magic de bloc (π) ou de fin de flux (√π)
+            [b'B', b'Z', b'h', b'1'..=b'9', 0x31, 0x41, 0x59, 0x26, 0x53, 0x59, ..]
+            | [b'B', b'Z', b'h', b'1'..=b'9', 0x17, 0x72, 0x45, 0x38, 0x50, 0x90, ..] => {
+                Compression::Bzip2
+            }
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+
+    /// Extension de fichier usuelle (`gz`, `zst`…), vide si non compressé.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+            Compression::Bzip2 => "bz2",
+            Compression::Xz => "xz",
+        }
+    }
+
+    /// Reconnaît une extension de fichier compressé.
+    pub fn from_extension(ext: &str) -> Self {
+        match ext.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" | "bgz" => Compression::Gzip,
+            "zst" | "zstd" => Compression::Zstd,
+            "bz2" => Compression::Bzip2,
+            "xz" => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Extension utile de `path`, en minuscules : celle sous l'extension de compression
+/// s'il y en a une (`data.CSV.gz` → `csv`), vide s'il n'y en a pas.
+pub fn inner_extension(path: &Path) -> String {
+    let ext_of = |p: &Path| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase()
+    };
+    let ext = ext_of(path);
+    if Compression::from_extension(&ext) == Compression::None {
+        return ext;
+    }
+    path.file_stem()
+        .map_or(String::new(), |stem| ext_of(Path::new(stem)))
+}
+
+/// Lecteur décompressant `reader` (flux multi-membres/multi-frames inclus).
+pub fn decoder<'a, R: BufRead + Send + 'a>(
+    reader: R,
+    compression: Compression,
+) -> Result<Box<dyn Read + Send + 'a>, ParseError> {
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+        Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+        Compression::Xz => Box::new(liblzma::bufread::XzDecoder::new_multi_decoder(reader)),
+    })
+}
+
+/// Détecte la compression en tête de flux et renvoie un lecteur décompressé
+/// (ou le flux tel quel s'il n'est pas compressé).
+pub fn decompress_reader<'a, R: Read + Send + 'a>(
+    mut reader: R,
+) -> Result<Box<dyn Read + Send + 'a>, ParseError> {
+    // Un pipe peut rendre moins d'octets que demandé : on lit l'en-tête explicitement.
+    let mut head = Vec::with_capacity(MAGIC_LEN);
+    reader
+        .by_ref()
+        .take(MAGIC_LEN as u64)
+        .read_to_end(&mut head)?;
+    let compression = Compression::detect(&head);
+    decoder(BufReader::new(Cursor::new(head).chain(reader)), compression)
+}
+
+/// Décompresse entièrement `data` en mémoire.
+///
+/// Les fichiers zstd multi-frames et gzip BGZF sont décompressés membre par membre en parallèle,
+/// directement dans le buffer final quand la taille de chaque membre est connue d'avance.
+pub fn decompress(data: &[u8], compression: Compression) -> Result<Vec<u8>, ParseError> {
+    let members = match compression {
+        Compression::Zstd => zstd_frames(data),
+        Compression::Gzip => bgzf_blocks(data),
+        _ => None,
+    };
+    let sized = members
+        .filter(|m| m.len() > 1)
+        .and_then(|m| Some((member_sizes(data, &m, compression)?, m)));
+    match sized {
+        Some((sizes, members)) => decompress_members(data, &members, &sizes, compression),
+        None => {
+            let mut out = Vec::with_capacity(data.len().saturating_mul(4).min(MAX_CAPACITY_HINT));
+            decoder(data, compression)?.read_to_end(&mut out)?;
+            Ok(out)
+        }
+    }
+}
+
+/// Taille décompressée annoncée par chaque membre (ISIZE BGZF, taille de contenu
+/// zstd), ou `None` si l'une manque.
+fn member_sizes(
+    data: &[u8],
+    members: &[(usize, usize)],
+    compression: Compression,
+) -> Option<Vec<usize>> {
+    members
+        .iter()
+        .map(|&(s, e)| match compression {
+            Compression::Gzip => Some(u32::from_le_bytes(data[e - 4..e].try_into().ok()?) as usize),
+            _ => usize::try_from(zstd::zstd_safe::get_frame_content_size(&data[s..e]).ok()??).ok(),
+        })
+        .collect()
+}
+
+/// Décompresse chaque membre en parallèle dans sa tranche du buffer final :
+/// pas de copie ni de double occupation mémoire.
+fn decompress_members(
+    data: &[u8],
+    members: &[(usize, usize)],
+    sizes: &[usize],
+    compression: Compression,
+) -> Result<Vec<u8>, ParseError> {
+    let total = sizes
+        .iter()
+        .try_fold(0usize, |acc, &n| acc.checked_add(n))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "decompressed size overflow"))?;
+    let mut out = Vec::new();
+    out.try_reserve_exact(total)
+        .map_err(|e| io::Error::new(io::ErrorKind::OutOfMemory, e))?;
+    out.resize(total, 0);
+
+    let mut slices = Vec::with_capacity(sizes.len());
+    let mut rest = &mut out[..];
+    for &n in sizes {
+        let (head, tail) = rest.split_at_mut(n);
+        slices.push(head);
+        rest = tail;
+    }
+    members
+        .par_iter()
+        .zip(slices)
+        .try_for_each(|(&(s, e), slice)| {
+            let mut member = decoder(&data[s..e], compression)?;
+            member.read_exact(slice)?;
+            // Taille annoncée trop petite : le membre n'est pas épuisé.
+            if member.read(&mut [0u8])? != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "member larger than its declared size",
+                )
+                .into());
+            }
+            Ok::<_, ParseError>(())
+        })?;
+    Ok(out)
+}
+
+/// Bornes des frames d'un flux zstd, ou `None` si le découpage échoue.
+fn zstd_frames(data: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let size = zstd::zstd_safe::find_frame_compressed_size(&data[pos..]).ok()?;
+        if size == 0 {
+            return None;
+        }
+        frames.push((pos, pos + size));
+        pos += size;
+    }
+    Some(frames)
+}
+
+/// Bornes des blocs BGZF (champ extra `BC` portant la taille du membre),
+/// ou `None` si un membre n'est pas au format BGZF.
+fn bgzf_blocks(data: &[u8]) -> Option<Vec<(usize, usize)>> {
+    const FEXTRA: u8 = 0x04;
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = data.get(pos..pos + 18)?;
+        if header[0..3] != [0x1f, 0x8b, 0x08] || header[3] & FEXTRA == 0 {
+            return None;
+        }
+        let xlen = u16::from_le_bytes([header[10], header[11]]) as usize;
+        let extra = data.get(pos + 12..pos + 12 + xlen)?;
+        let mut sub = extra;
+        let mut bsize = None;
+        while sub.len() >= 4 {
+            let slen = u16::from_le_bytes([sub[2], sub[3]]) as usize;
+            if sub[0..2] == *b"BC" && slen == 2 && sub.len() >= 6 {
+                bsize = Some(u16::from_le_bytes([sub[4], sub[5]]) as usize + 1);
+            }
+            sub = sub.get(4 + slen..)?;
+        }
+        let end = pos + bsize?;
+        if end > data.len() {
+            return None;
+        }
+        blocks.push((pos, end));
+        pos = end;
+    }
+    Some(blocks)
+}
+
+/// Ouvre un fichier : mmap s'il est brut, buffer décompressé s'il est compressé.
+pub fn open_data(path: &Path) -> Result<DocumentData, ParseError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { MmapOptions::new().map(&file)? };
+    match Compression::detect(&mmap) {
+        Compression::None => Ok(DocumentData::Mmap(Arc::new(mmap))),
+        c => Ok(DocumentData::Buffer(Arc::new(decompress(&mmap, c)?))),
+    }
+}
+
+/// Compression d'un fichier, d'après ses premiers octets.
+pub fn detect_file(path: &Path) -> Result<Compression, ParseError> {
+    let mut head = Vec::with_capacity(MAGIC_LEN);
+    File::open(path)?
+        .take(MAGIC_LEN as u64)
+        .read_to_end(&mut head)?;
+    Ok(Compression::detect(&head))
+}
+
+/// Lit un fichier entier en mémoire, décompressé si nécessaire.
+pub fn read_file(path: &Path) -> Result<Vec<u8>, ParseError> {
+    let data = std::fs::read(path)?;
+    match Compression::detect(&data) {
+        Compression::None => Ok(data),
+        c => decompress(&data, c),
+    }
+}
@@ -0,0 +1,268 @@
+//! Détection et transcodage des encodages non UTF-8 (Latin-1, Windows-1252, UTF-16).
+//!
+//! - Détection par BOM, sinon heuristique sur un échantillon de tête
+//! - Transcodage vers UTF-8 par chunks en parallèle (rayon), en buffer mémoire
+//! - Les données UTF-8 sans BOM restent zéro-copy (mmap conservé)
+
+use crate::error::snippet;
+use crate::{DocumentData, Location, ParseError};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Taille de l'échantillon analysé par l'heuristique (64 KiB).
+const SNIFF_LEN: usize = 64 * 1024;
+
+/// Encodage d'une entrée texte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// ISO-8859-1 : chaque octet est le code point de même valeur.
+    Latin1,
+    Windows1252,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Encodage signalé par un BOM en tête de `data`, avec la longueur du BOM.
+    pub fn from_bom(data: &[u8]) -> Option<(Self, usize)> {
+        match data {
+            [0xEF, 0xBB, 0xBF, ..] => Some((Encoding::Utf8, 3)),
+            [0xFF, 0xFE, ..] => Some((Encoding::Utf16Le, 2)),
+            [0xFE, 0xFF, ..] => Some((Encoding::Utf16Be, 2)),
+            _ => None,
+        }
+    }
+
+    /// Détecte l'encodage : BOM, puis heuristique sur les premiers 64 KiB.
+    ///
+    /// Sans BOM, un texte majoritairement ASCII en UTF-16 se reconnaît à ses octets nuls
+    /// alternés ; un échantillon qui n'est pas de l'UTF-8 valide est supposé Windows-1252
+    /// (sur-ensemble imprimable de Latin-1).
+    pub fn detect(data: &[u8]) -> Self {
+        if let Some((enc, _)) = Self::from_bom(data) {
+            return enc;
+        }
+        let sample = &data[..data.len().min(SNIFF_LEN)];
+        let pairs = sample.len() / 2;
+        if pairs > 0 {
+            let zeros_even = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+            let zeros_odd = sample
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .filter(|&&b| b == 0)
+                .count();
+            if zeros_odd * 3 > pairs && zeros_even * 10 < pairs {
+                return Encoding::Utf16Le;
+            }
+            if zeros_even * 3 > pairs && zeros_odd * 10 < pairs {
+                return Encoding::Utf16Be;
+            }
+        }
+        match std::str::from_utf8(sample) {
+            Ok(_) => Encoding::Utf8,
+            // Séquence tronquée par la fin de l'échantillon : l'UTF-8 reste plausible.
+            Err(e) if e.error_len().is_none() => Encoding::Utf8,
+            Err(_) => Encoding::Windows1252,
+        }
+    }
+
+    /// Nom usuel de l'encodage.
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Windows1252 => "windows-1252",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+        }
+    }
+
+    /// Longueur d'une unité de code (borne d'alignement des chunks).
+    fn unit_len(self) -> usize {
+        match self {
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            _ => 1,
+        }
+    }
+
+    /// Encodage `encoding_rs` correspondant ; `None` pour Latin-1, décodé à la main.
+    fn codec(self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            Encoding::Latin1 => None,
+            Encoding::Utf8 => Some(encoding_rs::UTF_8),
+            Encoding::Windows1252 => Some(encoding_rs::WINDOWS_1252),
+            Encoding::Utf16Le => Some(encoding_rs::UTF_16LE),
+            Encoding::Utf16Be => Some(encoding_rs::UTF_16BE),
+        }
+    }
+
+    /// Décode un morceau aligné sur les caractères ; `None` si `strict` et données invalides.
+    fn decode_chunk(self, bytes: &[u8], strict: bool) -> Option<Cow<'_, str>> {
+        let Some(enc) = self.codec() else {
+            return Some(Cow::Owned(bytes.iter().map(|&b| b as char).collect()));
+        };
+        if strict {
+            enc.decode_without_bom_handling_and_without_replacement(bytes)
+        } else {
+            Some(enc.decode_without_bom_handling(bytes).0)
+        }
+    }
+
+    /// Position de la première séquence invalide de `bytes`, s'il y en a une.
+    fn first_malformed(self, bytes: &[u8]) -> Option<usize> {
+        let mut decoder = self.codec()?.new_decoder_without_bom_handling();
+        let mut out = String::with_capacity(
+            decoder
+                .max_utf8_buffer_length_without_replacement(bytes.len())
+                .unwrap_or(bytes.len()),
+        );
+        match decoder.decode_to_string_without_replacement(bytes, &mut out, true) {
+            (encoding_rs::DecoderResult::Malformed(bad, after), read) => {
+                Some(read - bad as usize - after as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Vrai si l'unité de code en `i` est un saut de ligne.
+    fn is_newline_at(self, data: &[u8], i: usize) -> bool {
+        match self {
+            Encoding::Utf16Le => data[i..i + 2] == [b'\n', 0],
+            Encoding::Utf16Be => data[i..i + 2] == [0, b'\n'],
+            _ => data[i] == b'\n',
+        }
+    }
+
+    /// Localise l'octet `pos` de `data` (dans cet encodage) : ligne, colonne en octets
+    /// et extrait décodé.
+    ///
+    /// Coût linéaire : réservé aux chemins d'erreur.
+    fn locate(self, data: &[u8], pos: usize) -> Location {
+        let unit = self.unit_len();
+        let newline = |i: &usize| self.is_newline_at(data, *i);
+        let line_start = (0..pos)
+            .step_by(unit)
+            .rev()
+            .find(newline)
+            .map_or(0, |i| i + unit);
+        let line_end = (pos..(data.len() + 1).saturating_sub(unit))
+            .step_by(unit)
+            .find(newline)
+            .unwrap_or(data.len());
+        let decode =
+            |r: std::ops::Range<usize>| self.decode_chunk(&data[r], false).unwrap_or_default();
+        Location {
+            path: None,
+            line: Some((0..line_start).step_by(unit).filter(newline).count()),
+            byte_offset: Some(pos as u64),
+            column: Some(pos - line_start + 1),
+            snippet: Some(snippet(
+                decode(line_start..line_end).as_bytes(),
+                decode(line_start..pos).len(),
+            )),
+        }
+    }
+
+    /// Vrai si `unit` (deux octets UTF-16) est la moitié haute d'une paire de substitution.
+    fn is_high_surrogate(self, unit: [u8; 2]) -> bool {
+        let u = match self {
+            Encoding::Utf16Le => u16::from_le_bytes(unit),
+            _ => u16::from_be_bytes(unit),
+        };
+        (0xD800..0xDC00).contains(&u)
+    }
+}
+
+/// Bornes de chunks de `data` qui ne coupent ni une unité UTF-16 ni une paire de substitution.
+fn char_aligned_chunks(data: &[u8], chunk_size: usize, enc: Encoding) -> Vec<(usize, usize)> {
+    let unit = enc.unit_len();
+    let chunk_size = chunk_size.max(unit) / unit * unit;
+    let mut bounds = Vec::with_capacity(data.len() / chunk_size + 1);
+    let mut start = 0;
+    while start < data.len() {
+        let mut end = start.saturating_add(chunk_size).min(data.len());
+        if unit == 2 && end < data.len() && enc.is_high_surrogate([data[end - 2], data[end - 1]]) {
+            end += 2;
+        }
+        let end = end.min(data.len());
+        bounds.push((start, end));
+        start = end;
+    }
+    bounds
+}
+
+/// Transcode `data` (BOM exclu) en UTF-8, par chunks en parallèle.
+///
+/// En mode `strict`, des octets invalides pour l'encodage font échouer le transcodage
+/// avec une erreur localisée dans `data` ; sinon ils sont remplacés par U+FFFD.
+pub fn transcode(
+    data: &[u8],
+    enc: Encoding,
+    chunk_size: usize,
+    strict: bool,
+) -> Result<Vec<u8>, ParseError> {
+    transcode_from(data, 0, enc, chunk_size, strict)
+}
+
+/// Comme [`transcode`], sur `data[skip..]` ; les erreurs restent localisées dans `data`.
+fn transcode_from(
+    data: &[u8],
+    skip: usize,
+    enc: Encoding,
+    chunk_size: usize,
+    strict: bool,
+) -> Result<Vec<u8>, ParseError> {
+    let parts: Vec<Cow<'_, str>> = char_aligned_chunks(&data[skip..], chunk_size, enc)
+        .into_par_iter()
+        .map(|(s, e)| {
+            let (s, e) = (s + skip, e + skip);
+            enc.decode_chunk(&data[s..e], strict).ok_or_else(|| {
+                let pos = s + enc.first_malformed(&data[s..e]).unwrap_or(0);
+                ParseError::InvalidLine {
+                    location: Box::new(enc.locate(data, pos)),
+                    message: format!("invalid {} data", enc.name()),
+                }
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    let mut out = Vec::with_capacity(parts.iter().map(|p| p.len()).sum());
+    for p in &parts {
+        out.extend_from_slice(p.as_bytes());
+    }
+    Ok(out)
+}
+
+/// Données converties en UTF-8 prêtes à indexer, et octets de BOM à ignorer en tête.
+///
+/// Encodage retenu : `encoding` s'il est donné, sinon celui du BOM, sinon l'heuristique
+/// si `detect`, sinon UTF-8. L'UTF-8 reste tel quel (mmap conservé) ; les autres
+/// encodages passent par un buffer.
+pub fn to_utf8(
+    data: DocumentData,
+    encoding: Option<Encoding>,
+    detect: bool,
+    chunk_size: usize,
+    strict: bool,
+) -> Result<(DocumentData, usize), ParseError> {
+    let bytes = data.as_bytes();
+    let bom = Encoding::from_bom(bytes);
+    let enc = match (encoding, bom) {
+        (Some(enc), _) => enc,
+        (None, Some((enc, _))) => enc,
+        (None, None) if detect => Encoding::detect(bytes),
+        (None, None) => Encoding::Utf8,
+    };
+    // Un BOM n'est retiré que s'il correspond à l'encodage retenu.
+    let skip = match bom {
+        Some((b, len)) if b == enc => len,
+        _ => 0,
+    };
+    if enc == Encoding::Utf8 {
+        return Ok((data, skip));
+    }
+    let utf8 = transcode_from(bytes, skip, enc, chunk_size, strict)?;
+    Ok((DocumentData::Buffer(Arc::new(utf8)), 0))
+}
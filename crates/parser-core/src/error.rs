@@ -0,0 +1,212 @@
+//! Erreurs de parsing et localisation de la ligne fautive.
+//!
+//! Les variantes localisées portent fichier, index de ligne, offset et colonne,
+//! plus un extrait de la ligne affiché par `Display`.
+
+use memchr::{memchr, memchr_iter, memrchr};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Longueur maximale (octets) de l'extrait de ligne conservé.
+const SNIPPET_LEN: usize = 80;
+
+/// Position d'une erreur dans l'entrée.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Fichier source, si connu.
+    pub path: Option<PathBuf>,
+    /// Index de la ligne (base 0, comme `Document::get_line`).
+    pub line: Option<usize>,
+    /// Offset absolu en octets dans les données (décompressées).
+    pub byte_offset: Option<u64>,
+    /// Colonne en octets dans la ligne (base 1).
+    pub column: Option<usize>,
+    /// Extrait de la ligne autour de l'erreur.
+    pub snippet: Option<String>,
+}
+
+impl Location {
+    /// Localise l'octet `offset` de la ligne `line`, dont `bytes` est le contenu
+    /// et `line_start` l'offset de début.
+    pub fn in_line(bytes: &[u8], line: usize, line_start: u64, offset: u64) -> Self {
+        let col = (offset - line_start) as usize;
+        Location {
+            path: None,
+            line: Some(line),
+            byte_offset: Some(offset),
+            column: Some(col + 1),
+            snippet: Some(snippet(bytes, col)),
+        }
+    }
+
+    /// Localise l'octet `offset` d'un buffer entier (compte les lignes qui précèdent).
+    ///
+    /// Coût linéaire : réservé aux chemins d'erreur.
+    pub fn in_buffer(data: &[u8], offset: u64) -> Self {
+        let pos = (offset as usize).min(data.len());
+        let line_start = memrchr(b'\n', &data[..pos]).map_or(0, |p| p + 1);
+        let line_end = memchr(b'\n', &data[pos..]).map_or(data.len(), |p| pos + p);
+        let line = memchr_iter(b'\n', &data[..line_start]).count();
+        Self::in_line(
+            &data[line_start..line_end],
+            line,
+            line_start as u64,
+            pos as u64,
+        )
+    }
+
+    /// Localise une ligne/colonne en base 1 (convention serde_json).
+    pub fn at_line_column(line: usize, column: usize) -> Self {
+        Location {
+            line: line.checked_sub(1),
+            column: (column > 0).then_some(column),
+            ..Default::default()
+        }
+    }
+
+    /// Extrait affiché sous le message, précédé d'un saut de ligne.
+    fn snippet_suffix(&self) -> String {
+        match &self.snippet {
+            Some(s) => format!("\n  | {s}"),
+            None => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(p) = &self.path {
+            parts.push(p.display().to_string());
+        }
+        if let Some(l) = self.line {
+            parts.push(format!("line {}", l + 1));
+        }
+        if let Some(c) = self.column {
+            parts.push(format!("column {c}"));
+        }
+        if let Some(b) = self.byte_offset {
+            parts.push(format!("byte {b}"));
+        }
+        if parts.is_empty() {
+            f.write_str("unknown location")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+/// Extrait lisible d'une ligne, centré sur `col` si elle est trop longue.
+pub(crate) fn snippet(line: &[u8], col: usize) -> String {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let start = col.saturating_sub(SNIPPET_LEN / 2).min(line.len());
+    let end = (start + SNIPPET_LEN).min(line.len());
+    let mut s = String::from_utf8_lossy(&line[start..end]).into_owned();
+    if start > 0 {
+        s.insert_str(0, "...");
+    }
+    if end < line.len() {
+        s.push_str("...");
+    }
+    s
+}
+
+/// Catégorie d'une erreur JSON (reprend `serde_json::error::Category`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonErrorKind {
+    /// Erreur d'entrée/sortie pendant la lecture.
+    Io,
+    /// JSON syntaxiquement invalide.
+    Syntax,
+    /// JSON valide mais ne correspondant pas au type attendu.
+    Data,
+    /// Entrée tronquée.
+    Eof,
+}
+
+impl fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JsonErrorKind::Io => "I/O",
+            JsonErrorKind::Syntax => "syntax",
+            JsonErrorKind::Data => "data",
+            JsonErrorKind::Eof => "EOF",
+        })
+    }
+}
+
+/// Erreur de parsing. Les localisations sont boxées pour garder `Result` compact.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Format error: {0}")]
+    Format(String),
+    #[error("UTF-8 error: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("Index out of bounds: {0}")]
+    Index(usize),
+    #[error("Sidecar index error: {0}")]
+    Sidecar(String),
+    #[error("UTF-8 error at {location}: {source}{}", .location.snippet_suffix())]
+    InvalidUtf8 {
+        location: Box<Location>,
+        source: std::str::Utf8Error,
+    },
+    #[error("Format error at {location}: {message}{}", .location.snippet_suffix())]
+    InvalidLine {
+        location: Box<Location>,
+        message: String,
+    },
+    #[error("JSON {kind} error at {location}: {message}{}", .location.snippet_suffix())]
+    Json {
+        location: Box<Location>,
+        kind: JsonErrorKind,
+        message: String,
+    },
+}
+
+impl ParseError {
+    /// Erreur UTF-8 dans la ligne `line` (contenu `bytes`, commençant à `line_start`).
+    pub fn utf8_in_line(
+        bytes: &[u8],
+        line: usize,
+        line_start: u64,
+        source: std::str::Utf8Error,
+    ) -> Self {
+        let offset = line_start + source.valid_up_to() as u64;
+        ParseError::InvalidUtf8 {
+            location: Box::new(Location::in_line(bytes, line, line_start, offset)),
+            source,
+        }
+    }
+
+    /// Erreur UTF-8 sur un buffer entier, localisée d'après `valid_up_to`.
+    pub fn utf8_in_buffer(data: &[u8], source: std::str::Utf8Error) -> Self {
+        ParseError::InvalidUtf8 {
+            location: Box::new(Location::in_buffer(data, source.valid_up_to() as u64)),
+            source,
+        }
+    }
+
+    /// Localisation de l'erreur, si elle en porte une.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::InvalidUtf8 { location, .. }
+            | ParseError::InvalidLine { location, .. }
+            | ParseError::Json { location, .. } => Some(location.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Renseigne le fichier source d'une erreur localisée.
+    pub fn with_path(mut self, path: &Path) -> Self {
+        match &mut self {
+            ParseError::InvalidUtf8 { location, .. }
+            | ParseError::InvalidLine { location, .. }
+            | ParseError::Json { location, .. } => location.path = Some(path.to_path_buf()),
+            _ => {}
+        }
+        self
+    }
+}
@@ -0,0 +1,212 @@
+//! Document indexé à la demande.
+//!
+//! - Aucun scan à l'ouverture : seules les bornes de chunks sont calculées (une entrée
+//!   dans un autre encodage que l'UTF-8 est toutefois transcodée d'abord)
+//! - Chaque chunk est indexé (et validé UTF-8 en mode `Strict`) au premier accès
+//! - Index creux : un point de reprise toutes les `checkpoint_every` lignes
+//! - `get_line(n)` ne scanne que depuis le point de reprise le plus proche
+
+use crate::chunks::{aligned_chunks, check_utf8_lines, line_at_with, skip_lines_with};
+use crate::options::Validation;
+use crate::{encoding, DocumentData, ParseError, ParseOptions};
+use memchr::memchr_iter;
+use rayon::prelude::*;
+use std::sync::{Mutex, OnceLock};
+
+/// Taille de chunk par défaut pour l'indexation paresseuse (4 MiB).
+pub const DEFAULT_LAZY_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Index creux d'un chunk, construit au premier accès.
+struct ChunkIndex {
+    /// Nombre de lignes du chunk.
+    lines: usize,
+    /// Offset absolu des lignes 0, N, 2N… du chunk.
+    checkpoints: Vec<usize>,
+}
+
+/// Document dont l'index est construit chunk par chunk, au fil des accès.
+pub struct LazyDocument {
+    /// Garde le backend vivant.
+    pub data: DocumentData,
+    chunks: Vec<(usize, usize)>,
+    checkpoint_every: usize,
+    /// Terminateur, validation et pool pour les accès ultérieurs.
+    opts: ParseOptions,
+    states: Vec<OnceLock<Result<ChunkIndex, std::str::Utf8Error>>>,
+    /// Nombre cumulé de lignes à la fin de chaque chunk du préfixe déjà indexé.
+    line_ends: Mutex<Vec<usize>>,
+}
+
+impl LazyDocument {
+    /// Prépare un document paresseux sur `data`, découpé en chunks d'environ `chunk_size` octets.
+    pub fn new(
+        data: DocumentData,
+        chunk_size: usize,
+        checkpoint_every: usize,
+    ) -> Result<Self, ParseError> {
+        Self::with_options(
+            data,
+            checkpoint_every,
+            &ParseOptions::new().chunk_size(chunk_size),
+        )
+    }
+
+    /// Comme [`LazyDocument::new`], selon `opts` : taille de chunk, pool (pour
+    /// [`LazyDocument::line_count`]), terminateur, validation et encodage.
+    ///
+    /// Le BOM éventuel est ignoré ; hors validation `Strict`, l'UTF-8 est vérifié
+    /// ligne à ligne à l'accès.
+    pub fn with_options(
+        data: DocumentData,
+        checkpoint_every: usize,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let strict = opts.validation == Validation::Strict;
+        let (data, bom) = opts.install(|| {
+            encoding::to_utf8(
+                data,
+                opts.encoding,
+                opts.detect_encoding,
+                opts.chunk_size,
+                strict,
+            )
+        })?;
+        let chunks = aligned_chunks(
+            &data.as_bytes()[bom..],
+            opts.chunk_size,
+            opts.line_terminator.byte(),
+        )
+        .into_iter()
+        .map(|(s, e)| (s + bom, e + bom))
+        .collect::<Vec<_>>();
+        let states = chunks.iter().map(|_| OnceLock::new()).collect();
+        Ok(LazyDocument {
+            data,
+            chunks,
+            checkpoint_every: checkpoint_every.max(1),
+            opts: opts.clone(),
+            states,
+            line_ends: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Intervalle (en lignes) entre deux points de reprise.
+    pub fn checkpoint_every(&self) -> usize {
+        self.checkpoint_every
+    }
+
+    /// Nombre de chunks déjà indexés.
+    pub fn indexed_chunks(&self) -> usize {
+        self.states.iter().filter(|s| s.get().is_some()).count()
+    }
+
+    /// Index du chunk `i`, construit au premier appel.
+    fn chunk(&self, i: usize) -> Result<&ChunkIndex, ParseError> {
+        self.states[i]
+            .get_or_init(|| {
+                let (s, e) = self.chunks[i];
+                let slice = &self.data.as_bytes()[s..e];
+                // Les chunks finissent après un terminateur : validables indépendamment,
+                // ligne par ligne si le terminateur n'est pas ASCII.
+                if self.verified() {
+                    check_utf8_lines(slice, self.opts.line_terminator)?;
+                }
+                let term = self.opts.line_terminator.byte();
+                let mut checkpoints = vec![s];
+                let mut lines = 0;
+                for nl in memchr_iter(term, slice) {
+                    lines += 1;
+                    if lines % self.checkpoint_every == 0 && s + nl + 1 < e {
+                        checkpoints.push(s + nl + 1);
+                    }
+                }
+                if slice.last().is_some_and(|&b| b != term) {
+                    lines += 1;
+                }
+                Ok(ChunkIndex { lines, checkpoints })
+            })
+            .as_ref()
+            .map_err(|&e| ParseError::Utf8(e))
+    }
+
+    fn verified(&self) -> bool {
+        self.opts.validation == Validation::Strict
+    }
+
+    /// Texte de la ligne de longueur `len` en `start`, vérifié si besoin.
+    fn text<'a>(&self, data: &'a [u8], start: usize, len: usize) -> Result<&'a str, ParseError> {
+        let bytes = &data[start..start + len];
+        if self.verified() {
+            // Safety: chaque ligne du chunk validée telle quelle à l'indexation
+            // (`check_utf8_lines`) ; retirer un `\r` final la laisse valide.
+            Ok(unsafe { std::str::from_utf8_unchecked(bytes) })
+        } else {
+            std::str::from_utf8(bytes).map_err(ParseError::Utf8)
+        }
+    }
+
+    /// Nombre total de lignes ; indexe en parallèle tous les chunks restants.
+    pub fn line_count(&self) -> Result<usize, ParseError> {
+        self.opts.install(|| {
+            (0..self.chunks.len())
+                .into_par_iter()
+                .map(|i| self.chunk(i).map(|c| c.lines))
+                .sum()
+        })
+    }
+
+    /// Chunk contenant la ligne `idx` et numéro de sa première ligne : recherche
+    /// dichotomique dans le préfixe indexé, prolongé au besoin.
+    fn locate(&self, idx: usize) -> Result<Option<(usize, usize)>, ParseError> {
+        let mut ends = self.line_ends.lock().unwrap_or_else(|e| e.into_inner());
+        while ends.last().is_none_or(|&end| end <= idx) && ends.len() < self.chunks.len() {
+            let lines = self.chunk(ends.len())?.lines;
+            let end = ends.last().copied().unwrap_or(0) + lines;
+            ends.push(end);
+        }
+        let i = ends.partition_point(|&end| end <= idx);
+        if i == ends.len() {
+            return Ok(None);
+        }
+        Ok(Some((i, if i == 0 { 0 } else { ends[i - 1] })))
+    }
+
+    /// Accès à une ligne : indexe les chunks jusqu'à elle puis scanne depuis le point de reprise.
+    pub fn get_line(&self, idx: usize) -> Result<&str, ParseError> {
+        let Some((i, base)) = self.locate(idx)? else {
+            return Err(ParseError::Index(idx));
+        };
+        let chunk = self.chunk(i)?;
+        let local = idx - base;
+        let data = self.data.as_bytes();
+        let from = chunk.checkpoints[local / self.checkpoint_every];
+        let terminator = self.opts.line_terminator;
+        let start = skip_lines_with(data, from, local % self.checkpoint_every, terminator);
+        let (len, _) = line_at_with(data, start, terminator);
+        self.text(data, start, len)
+    }
+
+    /// Itérateur séquentiel sur les lignes ; les chunks sont indexés au fil de la lecture.
+    pub fn lines(&self) -> impl Iterator<Item = Result<&str, ParseError>> {
+        (0..self.chunks.len()).flat_map(move |i| {
+            let (s, e) = self.chunks[i];
+            let data = self.data.as_bytes();
+            let checked = self.chunk(i).map(|_| ());
+            let mut pos = s;
+            let mut error = checked.err();
+            std::iter::from_fn(move || {
+                if let Some(err) = error.take() {
+                    pos = e;
+                    return Some(Err(err));
+                }
+                if pos >= e {
+                    return None;
+                }
+                let (len, next) = line_at_with(data, pos, self.opts.line_terminator);
+                let line = self.text(data, pos, len);
+                pos = next;
+                Some(line)
+            })
+        })
+    }
+}
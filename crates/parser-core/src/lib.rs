@@ -7,7 +7,29 @@
 //! - API ergonomique pour serveurs ou batch
 
 use memmap2::Mmap;
-use std::{path::Path, sync::Arc};
+use options::Validation;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::{io::Read, path::Path, sync::Arc};
+
+pub mod chunks;
+pub mod decompress;
+pub mod encoding;
+mod error;
+pub mod lazy;
+mod line_index;
+pub mod options;
+pub mod sidecar;
+pub mod sparse;
+pub mod stream;
+
+pub use error::{JsonErrorKind, Location, ParseError};
+pub use lazy::LazyDocument;
+pub use line_index::{LineIndex, LineIndexIter, MappedIndex};
+pub use options::ParseOptions;
+pub use sidecar::IndexTag;
+pub use sparse::SparseDocument;
+pub use stream::LineStream;
 
 /// Backend de données pour Document : mmap ou buffer mémoire.
 pub enum DocumentData {
@@ -20,48 +42,273 @@ pub struct Document {
     /// Garde le backend vivant.
     pub data: DocumentData,
     /// Pour chaque ligne, (offset_en_octets, longueur_en_octets).
-    pub offsets: Vec<(u32, u32)>,
-}
-
-#[derive(thiserror::Error, Debug)]
-pub enum ParseError {
-    #[error("I/O error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("Format error: {0}")]
-    Format(String),
-    #[error("UTF-8 error: {0}")]
-    Utf8(#[from] std::str::Utf8Error),
-    #[error("Index out of bounds: {0}")]
-    Index(usize),
+    pub offsets: LineIndex,
+    /// Vrai si l'UTF-8 de chaque ligne a été vérifié au parsing.
+    verified: bool,
+    /// Index (dans `offsets`) des lignes invalides conservées (mode lossy), triés.
+    invalid: Vec<usize>,
+    /// Lignes invalides signalées (conservées en mode lossy, retirées en mode skip).
+    errors: Vec<ParseError>,
 }
 
 /// Tout parser de document doit implémenter ce trait.
 pub trait DocumentParser {
-    fn parse(path: &Path) -> Result<Document, ParseError>;
+    /// Identifiant du parser dans le tag des sidecars d'index.
+    const INDEX_KIND: u8;
+
+    /// Parse un fichier avec les options données.
+    fn parse_with(path: &Path, opts: &ParseOptions) -> Result<Document, ParseError>;
+
+    /// Parse des octets déjà chargés en mémoire avec les options données.
+    fn parse_bytes_with(data: Vec<u8>, opts: &ParseOptions) -> Result<Document, ParseError>;
+
+    fn parse(path: &Path) -> Result<Document, ParseError> {
+        Self::parse_with(path, &ParseOptions::default())
+    }
+
+    /// Parse des octets déjà chargés en mémoire (document en buffer).
+    fn parse_bytes(data: Vec<u8>) -> Result<Document, ParseError> {
+        Self::parse_bytes_with(data, &ParseOptions::default())
+    }
+
+    /// Parse depuis n'importe quel lecteur (stdin, pipe, socket…).
+    ///
+    /// Le contenu est chargé en mémoire ; pour une mémoire bornée, voir [`LineStream`].
+    fn parse_reader<R: Read>(reader: R) -> Result<Document, ParseError> {
+        Self::parse_bytes(stream::read_all(reader)?)
+    }
+
+    /// Découpage produit par ce parser avec `opts`, qui identifie ses sidecars.
+    fn index_tag(opts: &ParseOptions) -> IndexTag {
+        IndexTag::new(Self::INDEX_KIND, opts)
+    }
+
+    /// Parse en réutilisant le sidecar `<fichier>.mpidx` s'il est à jour,
+    /// sinon parse puis l'écrit pour les ouvertures suivantes.
+    fn parse_indexed(path: &Path) -> Result<Document, ParseError> {
+        Self::parse_indexed_with(path, &ParseOptions::default())
+    }
+
+    /// Comme [`DocumentParser::parse_indexed`], avec les options données ; un sidecar
+    /// écrit par un autre parser ou avec d'autres options est ignoré puis remplacé.
+    fn parse_indexed_with(path: &Path, opts: &ParseOptions) -> Result<Document, ParseError> {
+        let index_path = sidecar::default_index_path(path);
+        let tag = Self::index_tag(opts);
+        if let Ok(doc) = Document::open_indexed(path, &index_path, tag) {
+            return Ok(doc);
+        }
+        let doc = Self::parse_with(path, opts)?;
+        // Un sidecar impossible à écrire (dossier en lecture seule, buffer) n'empêche pas le parsing.
+        let _ = doc.save_index(path, &index_path, tag);
+        Ok(doc)
+    }
+}
+
+impl DocumentData {
+    /// Octets bruts du backend.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            DocumentData::Mmap(m) => &m[..],
+            DocumentData::Buffer(b) => &b[..],
+        }
+    }
 }
 
 impl Document {
-    /// Itérateur sur les lignes en &str, sans re-check UTF-8.
+    /// Document sans vérification UTF-8 préalable : les accesseurs `&str`
+    /// vérifient chaque ligne à la volée.
+    pub fn new(data: DocumentData, offsets: LineIndex) -> Self {
+        Document {
+            data,
+            offsets,
+            verified: false,
+            invalid: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Document dont toutes les lignes sont connues UTF-8 valides.
+    ///
     /// # Safety
-    /// Les offsets sont supposés corrects et pointent sur des tranches UTF-8 valides.
-    pub fn lines(&self) -> impl Iterator<Item = &str> {
-        self.offsets.iter().map(move |&(start, len)| {
-            let slice = match &self.data {
-                DocumentData::Mmap(m) => &m[start as usize..(start + len) as usize],
-                DocumentData::Buffer(b) => &b[start as usize..(start + len) as usize],
-            };
-            unsafe { std::str::from_utf8_unchecked(slice) }
+    /// Chaque entrée de `offsets` doit pointer sur une tranche UTF-8 valide de `data`.
+    pub unsafe fn new_unchecked(data: DocumentData, offsets: LineIndex) -> Self {
+        Document {
+            verified: true,
+            ..Self::new(data, offsets)
+        }
+    }
+
+    /// Transcode si besoin en UTF-8, indexe les lignes en parallèle puis applique
+    /// la politique de validation, le tout dans le pool configuré.
+    pub fn from_data(data: DocumentData, opts: &ParseOptions) -> Result<Self, ParseError> {
+        Self::from_data_with(data, opts, |bytes, bom| {
+            chunks::index_lines_from(bytes, bom, opts.chunk_size, opts.line_terminator)
+        })
+    }
+
+    /// Comme [`Document::from_data`], avec un indexeur propre au format
+    /// (appelé sur les données UTF-8 et la longueur du BOM à ignorer).
+    pub fn from_data_with<F>(
+        data: DocumentData,
+        opts: &ParseOptions,
+        index: F,
+    ) -> Result<Self, ParseError>
+    where
+        F: FnOnce(&[u8], usize) -> LineIndex + Send,
+    {
+        opts.install(|| {
+            let strict = opts.validation == Validation::Strict;
+            let (data, bom) = encoding::to_utf8(
+                data,
+                opts.encoding,
+                opts.detect_encoding,
+                opts.chunk_size,
+                strict,
+            )?;
+            let offsets = index(data.as_bytes(), bom);
+            Self::validated(data, offsets, opts)
+        })
+    }
+
+    /// Construit le document en appliquant la longueur max et la politique
+    /// de validation de `opts` (`Strict`, `Lossy`, `Skip` ou `Off`).
+    pub fn validated(
+        data: DocumentData,
+        offsets: LineIndex,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let bytes = data.as_bytes();
+        let line = |i: usize| {
+            let (start, len) = offsets.get(i).unwrap();
+            (start, &bytes[start as usize..(start + len) as usize])
+        };
+        if let Some(max) = opts.max_line_length {
+            let too_long = (0..offsets.len())
+                .into_par_iter()
+                .with_min_len(4096)
+                .find_first(|&i| line(i).1.len() > max);
+            if let Some(i) = too_long {
+                let (start, bytes) = line(i);
+                return Err(ParseError::InvalidLine {
+                    location: Box::new(Location::in_line(bytes, i, start, start + max as u64)),
+                    message: format!("line too long: {} bytes (max {max})", bytes.len()),
+                });
+            }
+        }
+        match opts.validation {
+            Validation::Off => return Ok(Self::new(data, offsets)),
+            Validation::Strict => {
+                let bad = (0..offsets.len())
+                    .into_par_iter()
+                    .with_min_len(4096)
+                    .find_map_first(|i| {
+                        let (start, bytes) = line(i);
+                        let e = std::str::from_utf8(bytes).err()?;
+                        Some(ParseError::utf8_in_line(bytes, i, start, e))
+                    });
+                return match bad {
+                    Some(e) => Err(e),
+                    // Safety: toutes les lignes viennent d'être vérifiées.
+                    None => Ok(unsafe { Self::new_unchecked(data, offsets) }),
+                };
+            }
+            Validation::Lossy | Validation::Skip => {}
+        }
+        let invalid: Vec<usize> = (0..offsets.len())
+            .into_par_iter()
+            .with_min_len(4096)
+            .filter(|&i| std::str::from_utf8(line(i).1).is_err())
+            .collect();
+        let errors: Vec<ParseError> = invalid
+            .iter()
+            .map(|&i| {
+                let (start, bytes) = line(i);
+                let e = std::str::from_utf8(bytes).unwrap_err();
+                ParseError::utf8_in_line(bytes, i, start, e)
+            })
+            .collect();
+        let (offsets, invalid) = match opts.validation {
+            Validation::Skip if !invalid.is_empty() => {
+                let mut kept = LineIndex::with_capacity(bytes.len() as u64, offsets.len());
+                let mut bad = invalid.iter().peekable();
+                for (i, (start, len)) in offsets.iter().enumerate() {
+                    if bad.next_if_eq(&&i).is_none() {
+                        kept.push(start, len);
+                    }
+                }
+                (kept, Vec::new())
+            }
+            Validation::Lossy => (offsets, invalid),
+            _ => (offsets, Vec::new()),
+        };
+        Ok(Document {
+            data,
+            offsets,
+            verified: true,
+            invalid,
+            errors,
         })
     }
 
+    /// Tranche brute de la ligne `idx`, (offset, longueur).
+    ///
+    /// Les bornes sont vérifiées ici plutôt qu'à l'ouverture : un index relu d'un
+    /// sidecar n'est pas parcouru en entier.
+    #[inline]
+    fn slice(&self, idx: usize, start: u64, len: u64) -> Result<&[u8], ParseError> {
+        let bytes = self.data.as_bytes();
+        usize::try_from(start)
+            .ok()
+            .zip(usize::try_from(len).ok())
+            .and_then(|(start, len)| bytes.get(start..start.checked_add(len)?))
+            .ok_or_else(|| {
+                ParseError::Sidecar(format!(
+                    "l'entrée {idx} ({start}+{len}) dépasse la fin des données ({} octets)",
+                    bytes.len()
+                ))
+            })
+    }
+
+    /// Ligne `idx` en &str si elle est UTF-8 valide, sans re-check quand c'est déjà établi.
+    #[inline]
+    fn checked_line(&self, idx: usize, start: u64, len: u64) -> Result<&str, ParseError> {
+        let slice = self.slice(idx, start, len)?;
+        if self.verified && self.invalid.binary_search(&idx).is_err() {
+            // Safety: ligne validée au parsing (voir `validated` / `new_unchecked`)
+            Ok(unsafe { std::str::from_utf8_unchecked(slice) })
+        } else {
+            std::str::from_utf8(slice).map_err(|e| ParseError::utf8_in_line(slice, idx, start, e))
+        }
+    }
+
+    /// Itérateur sur les lignes UTF-8 valides, en &str.
+    ///
+    /// Les lignes invalides (mode lossy ou document non vérifié) sont omises :
+    /// voir [`Document::lines_lossy`] pour les obtenir avec remplacement.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.offsets
+            .iter()
+            .enumerate()
+            .filter_map(move |(idx, (start, len))| self.checked_line(idx, start, len).ok())
+    }
+
+    /// Itérateur sur toutes les lignes, octets invalides remplacés par U+FFFD.
+    ///
+    /// Seules les entrées hors des données (sidecar corrompu) sont omises.
+    pub fn lines_lossy(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.offsets
+            .iter()
+            .enumerate()
+            .filter_map(move |(idx, (start, len))| self.slice(idx, start, len).ok())
+            .map(String::from_utf8_lossy)
+    }
+
     /// Retourne un Vec<&str> correspondant à un range de lignes [start, end).
     pub fn lines_range(&self, start: usize, end: usize) -> Result<Vec<&str>, ParseError> {
         if start > end || end > self.offsets.len() {
             return Err(ParseError::Index(end));
         }
-        Ok((start..end)
-            .map(|idx| self.get_line(idx).unwrap())
-            .collect())
+        (start..end).map(|idx| self.get_line(idx)).collect()
     }
 
     /// Nombre de lignes dans le document.
@@ -70,32 +317,54 @@ impl Document {
     }
 
     /// Accès à une ligne précise (avec vérification des bornes).
+    ///
+    /// Une ligne invalide renvoie une erreur localisée au lieu d'un &str.
     pub fn get_line(&self, idx: usize) -> Result<&str, ParseError> {
-        if let Some(&(start, len)) = self.offsets.get(idx) {
-            let slice = match &self.data {
-                DocumentData::Mmap(m) => &m[start as usize..(start + len) as usize],
-                DocumentData::Buffer(b) => &b[start as usize..(start + len) as usize],
-            };
-            // Safety: offsets garantis valides par le parser
-            Ok(unsafe { std::str::from_utf8_unchecked(slice) })
-        } else {
-            Err(ParseError::Index(idx))
+        match self.offsets.get(idx) {
+            Some((start, len)) => self.checked_line(idx, start, len),
+            None => Err(ParseError::Index(idx)),
+        }
+    }
+
+    /// Accès à une ligne précise, octets invalides remplacés par U+FFFD.
+    pub fn get_line_lossy(&self, idx: usize) -> Result<Cow<'_, str>, ParseError> {
+        match self.offsets.get(idx) {
+            Some((start, len)) => Ok(String::from_utf8_lossy(self.slice(idx, start, len)?)),
+            None => Err(ParseError::Index(idx)),
         }
     }
 
     /// Accès à une ligne précise, vérification UTF-8 à la volée (safe).
+    ///
+    /// Une ligne invalide renvoie une erreur localisée (ligne, offset, colonne).
     pub fn get_line_safe(&self, idx: usize) -> Result<&str, ParseError> {
-        if let Some(&(start, len)) = self.offsets.get(idx) {
-            let slice = match &self.data {
-                DocumentData::Mmap(m) => &m[start as usize..(start + len) as usize],
-                DocumentData::Buffer(b) => &b[start as usize..(start + len) as usize],
-            };
-            std::str::from_utf8(slice).map_err(ParseError::Utf8)
+        if let Some((start, len)) = self.offsets.get(idx) {
+            let slice = self.slice(idx, start, len)?;
+            std::str::from_utf8(slice).map_err(|e| ParseError::utf8_in_line(slice, idx, start, e))
         } else {
             Err(ParseError::Index(idx))
         }
     }
 
+    /// Index des lignes invalides conservées (mode lossy).
+    pub fn invalid_lines(&self) -> &[usize] {
+        &self.invalid
+    }
+
+    /// Erreurs UTF-8 des lignes invalides, localisées dans le fichier d'origine.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    /// Renseigne le fichier source des erreurs signalées.
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.errors = std::mem::take(&mut self.errors)
+            .into_iter()
+            .map(|e| e.with_path(path))
+            .collect();
+        self
+    }
+
     /// Itérateur streaming (sans indexation préalable, pour très gros fichiers)
     /// Peut être utilisé par un parser alternatif.
     pub fn streaming_lines(data: &[u8]) -> impl Iterator<Item = Result<&str, std::str::Utf8Error>> {
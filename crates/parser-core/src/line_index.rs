@@ -0,0 +1,254 @@
+//! Index des lignes d'un document : (offset, longueur) en octets.
+//!
+//! - Représentation compacte `u32` tant que le fichier tient sous 4 GiB
+//! - Bascule automatique en `u64` au-delà (fichiers de 100+ Go)
+//! - Variante mappée : index relu directement depuis un fichier sidecar
+
+use memmap2::Mmap;
+use std::sync::Arc;
+
+/// Index (offset, longueur) de chaque ligne.
+///
+/// Les petits fichiers gardent 8 octets par ligne ; seul un offset ou une
+/// longueur dépassant `u32::MAX` fait passer l'index en représentation large.
+#[derive(Debug, Clone)]
+pub enum LineIndex {
+    /// Offsets et longueurs tiennent sur 32 bits.
+    Compact(Vec<(u32, u32)>),
+    /// Offsets et longueurs sur 64 bits (fichiers > 4 GiB).
+    Wide(Vec<(u64, u64)>),
+    /// Entrées lues à la demande dans un sidecar mmappé (voir `sidecar`).
+    Mapped(MappedIndex),
+}
+
+/// Entrées d'index little-endian stockées dans un mmap (zéro-copy).
+#[derive(Debug, Clone)]
+pub struct MappedIndex {
+    map: Arc<Mmap>,
+    /// Position du premier enregistrement dans le mmap.
+    base: usize,
+    len: usize,
+    wide: bool,
+}
+
+impl MappedIndex {
+    /// Construit la vue ; l'appelant garantit que `base + len * taille_entrée` tient dans `map`.
+    pub(crate) fn new(map: Arc<Mmap>, base: usize, len: usize, wide: bool) -> Self {
+        MappedIndex {
+            map,
+            base,
+            len,
+            wide,
+        }
+    }
+
+    /// Taille d'une entrée en octets.
+    pub(crate) fn entry_size(wide: bool) -> usize {
+        if wide {
+            16
+        } else {
+            8
+        }
+    }
+
+    #[inline]
+    fn get(&self, idx: usize) -> Option<(u64, u64)> {
+        if idx >= self.len {
+            return None;
+        }
+        let at = self.base + idx * Self::entry_size(self.wide);
+        let bytes = &self.map[at..];
+        Some(if self.wide {
+            (
+                u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+                u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            )
+        } else {
+            (
+                u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as u64,
+                u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as u64,
+            )
+        })
+    }
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        LineIndex::Compact(Vec::new())
+    }
+}
+
+impl PartialEq for LineIndex {
+    /// Deux index sont égaux s'ils décrivent les mêmes lignes, quelle que soit la représentation.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for LineIndex {}
+
+impl LineIndex {
+    /// Index vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index vide dimensionné pour des données de `data_len` octets.
+    ///
+    /// Choisit directement la représentation large si `data_len` dépasse 32 bits,
+    /// ce qui évite toute conversion pendant le remplissage.
+    pub fn with_capacity(data_len: u64, capacity: usize) -> Self {
+        if data_len > u32::MAX as u64 {
+            LineIndex::Wide(Vec::with_capacity(capacity))
+        } else {
+            LineIndex::Compact(Vec::with_capacity(capacity))
+        }
+    }
+
+    /// Nombre de lignes indexées.
+    pub fn len(&self) -> usize {
+        match self {
+            LineIndex::Compact(v) => v.len(),
+            LineIndex::Wide(v) => v.len(),
+            LineIndex::Mapped(m) => m.len,
+        }
+    }
+
+    /// Vrai si aucune ligne n'est indexée.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Vrai si l'index utilise la représentation 64 bits.
+    pub fn is_wide(&self) -> bool {
+        match self {
+            LineIndex::Compact(_) => false,
+            LineIndex::Wide(_) => true,
+            LineIndex::Mapped(m) => m.wide,
+        }
+    }
+
+    /// (offset, longueur) de la ligne `idx`.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<(u64, u64)> {
+        match self {
+            LineIndex::Compact(v) => v.get(idx).map(|&(s, l)| (s as u64, l as u64)),
+            LineIndex::Wide(v) => v.get(idx).copied(),
+            LineIndex::Mapped(m) => m.get(idx),
+        }
+    }
+
+    /// Ajoute une ligne, en passant en 64 bits si nécessaire.
+    ///
+    /// Un index mappé est d'abord recopié en mémoire.
+    #[inline]
+    pub fn push(&mut self, start: u64, len: u64) {
+        if let LineIndex::Mapped(_) = self {
+            self.materialize();
+        }
+        if let LineIndex::Compact(v) = self {
+            if let (Ok(s), Ok(l)) = (u32::try_from(start), u32::try_from(len)) {
+                v.push((s, l));
+                return;
+            }
+            self.widen();
+        }
+        if let LineIndex::Wide(v) = self {
+            v.push((start, len));
+        }
+    }
+
+    /// Concatène `other` à la suite de cet index.
+    pub fn append(&mut self, other: LineIndex) {
+        if let LineIndex::Mapped(_) = self {
+            self.materialize();
+        }
+        match (&mut *self, other) {
+            (LineIndex::Compact(a), LineIndex::Compact(mut b)) => a.append(&mut b),
+            (LineIndex::Wide(a), LineIndex::Wide(mut b)) => a.append(&mut b),
+            (LineIndex::Wide(a), LineIndex::Compact(b)) => {
+                a.extend(b.into_iter().map(|(s, l)| (s as u64, l as u64)))
+            }
+            (_, other) => {
+                for (s, l) in other.iter() {
+                    self.push(s, l);
+                }
+            }
+        }
+    }
+
+    /// Itérateur sur les (offset, longueur) de toutes les lignes.
+    pub fn iter(&self) -> LineIndexIter<'_> {
+        match self {
+            LineIndex::Compact(v) => LineIndexIter::Compact(v.iter()),
+            LineIndex::Wide(v) => LineIndexIter::Wide(v.iter()),
+            LineIndex::Mapped(m) => LineIndexIter::Mapped(m, 0..m.len),
+        }
+    }
+
+    /// Convertit l'index en représentation 64 bits.
+    fn widen(&mut self) {
+        if let LineIndex::Compact(v) = self {
+            let wide = v.iter().map(|&(s, l)| (s as u64, l as u64)).collect();
+            *self = LineIndex::Wide(wide);
+        }
+    }
+
+    /// Recopie un index mappé dans la représentation mémoire équivalente.
+    fn materialize(&mut self) {
+        if let LineIndex::Mapped(m) = self {
+            *self = if m.wide {
+                LineIndex::Wide((0..m.len).map(|i| m.get(i).unwrap()).collect())
+            } else {
+                LineIndex::Compact(
+                    (0..m.len)
+                        .map(|i| m.get(i).unwrap())
+                        .map(|(s, l)| (s as u32, l as u32))
+                        .collect(),
+                )
+            };
+        }
+    }
+}
+
+impl From<Vec<(u32, u32)>> for LineIndex {
+    fn from(v: Vec<(u32, u32)>) -> Self {
+        LineIndex::Compact(v)
+    }
+}
+
+impl From<Vec<(u64, u64)>> for LineIndex {
+    fn from(v: Vec<(u64, u64)>) -> Self {
+        LineIndex::Wide(v)
+    }
+}
+
+/// Itérateur sur un [`LineIndex`], quelle que soit sa représentation.
+pub enum LineIndexIter<'a> {
+    Compact(std::slice::Iter<'a, (u32, u32)>),
+    Wide(std::slice::Iter<'a, (u64, u64)>),
+    Mapped(&'a MappedIndex, std::ops::Range<usize>),
+}
+
+impl Iterator for LineIndexIter<'_> {
+    type Item = (u64, u64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LineIndexIter::Compact(it) => it.next().map(|&(s, l)| (s as u64, l as u64)),
+            LineIndexIter::Wide(it) => it.next().copied(),
+            LineIndexIter::Mapped(m, range) => range.next().and_then(|i| m.get(i)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            LineIndexIter::Compact(it) => it.size_hint(),
+            LineIndexIter::Wide(it) => it.size_hint(),
+            LineIndexIter::Mapped(_, range) => range.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for LineIndexIter<'_> {}
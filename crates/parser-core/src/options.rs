@@ -0,0 +1,213 @@
+//! Options de parsing communes à tous les parsers.
+//!
+//! Remplace les constantes codées en dur (taille de chunk, seuil JSON, madvise…)
+//! par un builder passé à [`crate::DocumentParser::parse_with`].
+
+use crate::chunks::DEFAULT_CHUNK_SIZE;
+use crate::encoding::Encoding;
+use crate::DocumentData;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+
+/// Seuil par défaut sous lequel un fichier est chargé entièrement en mémoire (512 MiB).
+pub const DEFAULT_IN_MEMORY_THRESHOLD: u64 = 512 * 1024 * 1024;
+
+/// Niveau de validation du contenu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    /// Aucune vérification au parsing : chaque ligne est vérifiée à l'accès.
+    Off,
+    /// Toute ligne invalide fait échouer le parsing.
+    #[default]
+    Strict,
+    /// Lignes invalides conservées, lisibles via les accesseurs `*_lossy`.
+    Lossy,
+    /// Lignes invalides retirées du document et signalées dans `Document::errors`.
+    Skip,
+}
+
+/// Délimiteur de fin de ligne.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineTerminator {
+    /// `\n`, avec `\r` final retiré : accepte `\n` et `\r\n`.
+    #[default]
+    Auto,
+    /// `\n` seul : un `\r` final reste dans la ligne.
+    Lf,
+    /// Octet arbitraire (`\0`, `\x1e`…), sans traitement de `\r`.
+    Byte(u8),
+}
+
+impl LineTerminator {
+    /// Octet recherché pour couper les lignes.
+    pub fn byte(self) -> u8 {
+        match self {
+            LineTerminator::Auto | LineTerminator::Lf => b'\n',
+            LineTerminator::Byte(b) => b,
+        }
+    }
+
+    /// Vrai si un `\r` précédant le terminateur est retiré de la ligne.
+    pub fn strips_cr(self) -> bool {
+        self == LineTerminator::Auto
+    }
+}
+
+/// Conseil d'accès mémoire donné au kernel pour les fichiers mmappés.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Madvise {
+    /// Pas de conseil.
+    Normal,
+    /// Readahead agressif, adapté au scan complet.
+    #[default]
+    Sequential,
+    /// Accès aléatoire (`get_line` ponctuels), pas de readahead.
+    Random,
+    /// Précharge tout le fichier.
+    WillNeed,
+}
+
+impl Madvise {
+    /// Applique le conseil au mmap (sans effet sur un buffer mémoire ou hors unix).
+    pub fn apply(self, data: &DocumentData) {
+        #[cfg(unix)]
+        if let DocumentData::Mmap(m) = data {
+            let advice = match self {
+                Madvise::Normal => libc::POSIX_MADV_NORMAL,
+                Madvise::Sequential => libc::POSIX_MADV_SEQUENTIAL,
+                Madvise::Random => libc::POSIX_MADV_RANDOM,
+                Madvise::WillNeed => libc::POSIX_MADV_WILLNEED,
+            };
+            if !m.is_empty() {
+                unsafe {
+                    libc::posix_madvise(m.as_ptr() as *mut _, m.len() as libc::size_t, advice);
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = data;
+    }
+}
+
+/// Options de parsing, construites par chaînage.
+///
+/// ```
+/// use parser_core::options::{Madvise, ParseOptions};
+/// let opts = ParseOptions::new()
+///     .chunk_size(16 * 1024 * 1024)
+///     .threads(4)
+///     .madvise(Madvise::Random)
+///     .max_line_length(1 << 20);
+/// assert_eq!(opts.chunk_size, 16 * 1024 * 1024);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Taille nominale des chunks du scan parallèle.
+    pub chunk_size: usize,
+    /// Nombre de threads rayon (pool dédié construit à chaque parsing).
+    pub threads: Option<usize>,
+    /// Pool rayon fourni par l'appelant, prioritaire sur `threads`.
+    pub pool: Option<Arc<ThreadPool>>,
+    /// Niveau de validation.
+    pub validation: Validation,
+    /// Délimiteur de fin de ligne.
+    pub line_terminator: LineTerminator,
+    /// Conseil madvise pour les fichiers mmappés.
+    pub madvise: Madvise,
+    /// Taille (octets) sous laquelle un fichier est chargé d'un bloc (JSON).
+    pub in_memory_threshold: u64,
+    /// Longueur maximale d'une ligne, en octets.
+    pub max_line_length: Option<usize>,
+    /// Encodage de l'entrée ; `None` : BOM s'il y en a un, sinon UTF-8 (ou heuristique).
+    pub encoding: Option<Encoding>,
+    /// Sans encodage explicite ni BOM, devine l'encodage sur un échantillon de tête.
+    pub detect_encoding: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            threads: None,
+            pool: None,
+            validation: Validation::default(),
+            line_terminator: LineTerminator::default(),
+            madvise: Madvise::default(),
+            in_memory_threshold: DEFAULT_IN_MEMORY_THRESHOLD,
+            max_line_length: None,
+            encoding: None,
+            detect_encoding: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options par défaut (comportement historique des parsers).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    pub fn thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    pub fn validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    pub fn line_terminator(mut self, line_terminator: LineTerminator) -> Self {
+        self.line_terminator = line_terminator;
+        self
+    }
+
+    pub fn madvise(mut self, madvise: Madvise) -> Self {
+        self.madvise = madvise;
+        self
+    }
+
+    pub fn in_memory_threshold(mut self, bytes: u64) -> Self {
+        self.in_memory_threshold = bytes;
+        self
+    }
+
+    pub fn max_line_length(mut self, bytes: usize) -> Self {
+        self.max_line_length = Some(bytes);
+        self
+    }
+
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    pub fn detect_encoding(mut self, detect: bool) -> Self {
+        self.detect_encoding = detect;
+        self
+    }
+
+    /// Exécute `f` dans le pool rayon configuré (ou le pool global par défaut).
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        if let Some(pool) = &self.pool {
+            return pool.install(f);
+        }
+        match self
+            .threads
+            .and_then(|n| ThreadPoolBuilder::new().num_threads(n).build().ok())
+        {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Index de lignes persistant sur disque (fichier sidecar).
+//!
+//! - En-tête : taille, mtime et empreinte du fichier source (invalidation)
+//! - Découpage ([`IndexTag`]) : parser, terminateur, guillemet, encodage, validation
+//! - Entrées little-endian relues par mmap, sans reconstruction
+//! - Réouverture quasi instantanée, `get_line` disponible immédiatement
+//!
+//! Format (little-endian) :
+//!
+//! | octets | contenu                                  |
+//! |--------|------------------------------------------|
+//! | 0..8   | magic `MPIDX001`                         |
+//! | 8..16  | taille du fichier source                 |
+//! | 16..24 | mtime, secondes depuis l'epoch           |
+//! | 24..28 | mtime, nanosecondes                      |
+//! | 28..32 | flags (bit 0 : entrées 64 bits) + tag    |
+//! | 32..40 | empreinte FNV-1a (début + fin du source) |
+//! | 40..48 | nombre de lignes                         |
+//! | 48..   | entrées (offset, longueur)               |
+
+use crate::encoding::Encoding;
+use crate::line_index::MappedIndex;
+use crate::options::{LineTerminator, Validation};
+use crate::{Document, DocumentData, LineIndex, ParseError, ParseOptions};
+use memmap2::MmapOptions;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+const MAGIC: &[u8; 8] = b"MPIDX001";
+const HEADER_LEN: usize = 48;
+const FLAG_WIDE: u32 = 1;
+/// Octets hachés en début et en fin de fichier pour l'empreinte.
+const HASH_SAMPLE: usize = 64 * 1024;
+
+/// Extension ajoutée au chemin du fichier source pour son sidecar.
+pub const INDEX_EXTENSION: &str = "mpidx";
+
+/// Chemin par défaut du sidecar : `fichier.txt` → `fichier.txt.mpidx`.
+pub fn default_index_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(INDEX_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Découpage décrit par un sidecar : un index n'est réutilisé que par le parser
+/// et les options qui l'ont produit.
+///
+/// Stocké dans les bits 1..32 des flags : bit 1 `\r` retiré, bit 2 lignes invalides
+/// retirées, bit 3 guillemet, bits 4..7 encodage imposé, bit 7 détection, bits 8..16
+/// terminateur, bits 16..24 guillemet, bits 24..32 parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexTag {
+    kind: u8,
+    terminator: LineTerminator,
+    quote: Option<u8>,
+    encoding: Option<Encoding>,
+    detect_encoding: bool,
+    skips_invalid: bool,
+}
+
+impl IndexTag {
+    /// Tag du parser `kind` (voir [`crate::DocumentParser::INDEX_KIND`]) avec `opts`.
+    pub fn new(kind: u8, opts: &ParseOptions) -> Self {
+        IndexTag {
+            kind,
+            terminator: opts.line_terminator,
+            quote: None,
+            encoding: opts.encoding,
+            detect_encoding: opts.detect_encoding,
+            skips_invalid: opts.validation == Validation::Skip,
+        }
+    }
+
+    /// Enregistrements délimités en tenant compte du guillemet `quote` (CSV).
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    fn bits(self) -> u32 {
+        let encoding = match self.encoding {
+            None => 0,
+            Some(Encoding::Utf8) => 1,
+            Some(Encoding::Latin1) => 2,
+            Some(Encoding::Windows1252) => 3,
+            Some(Encoding::Utf16Le) => 4,
+            Some(Encoding::Utf16Be) => 5,
+        };
+        u32::from(self.terminator.strips_cr()) << 1
+            | u32::from(self.skips_invalid) << 2
+            | u32::from(self.quote.is_some()) << 3
+            | encoding << 4
+            | u32::from(self.detect_encoding) << 7
+            | u32::from(self.terminator.byte()) << 8
+            | u32::from(self.quote.unwrap_or(0)) << 16
+            | u32::from(self.kind) << 24
+    }
+}
+
+/// Identité du fichier source au moment de l'indexation.
+#[derive(Debug, PartialEq, Eq)]
+struct Fingerprint {
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    hash: u64,
+}
+
+impl Fingerprint {
+    fn of(source: &Path, data: &[u8]) -> Result<Self, ParseError> {
+        let meta = std::fs::metadata(source)?;
+        let mtime = meta
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Fingerprint {
+            size: meta.len(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            hash: sample_hash(data),
+        })
+    }
+}
+
+/// FNV-1a 64 bits sur la taille, le début et la fin des données.
+fn sample_hash(data: &[u8]) -> u64 {
+    let head = &data[..data.len().min(HASH_SAMPLE)];
+    let tail = &data[data.len().saturating_sub(HASH_SAMPLE)..];
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in (data.len() as u64)
+        .to_le_bytes()
+        .iter()
+        .chain(head)
+        .chain(tail)
+    {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    h
+}
+
+fn le_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+impl Document {
+    /// Sauvegarde l'index de lignes dans `index_path`, lié au fichier `source` et au
+    /// découpage `tag`.
+    ///
+    /// Seuls les documents mmappés sur `source` ont des offsets réutilisables ;
+    /// un document en buffer mémoire est refusé.
+    pub fn save_index(
+        &self,
+        source: &Path,
+        index_path: &Path,
+        tag: IndexTag,
+    ) -> Result<(), ParseError> {
+        let data = match &self.data {
+            DocumentData::Mmap(m) => &m[..],
+            DocumentData::Buffer(_) => {
+                return Err(ParseError::Sidecar(
+                    "document en buffer mémoire, offsets non liés au fichier".into(),
+                ))
+            }
+        };
+        let fp = Fingerprint::of(source, data)?;
+        if fp.size != data.len() as u64 {
+            return Err(ParseError::Sidecar(format!(
+                "{} a changé depuis le parsing",
+                source.display()
+            )));
+        }
+        let wide = self.offsets.is_wide();
+
+        // Écriture dans un fichier temporaire puis renommage : jamais de sidecar tronqué.
+        let mut tmp_path = index_path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let mut out = BufWriter::new(File::create(&tmp_path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&fp.size.to_le_bytes())?;
+        out.write_all(&fp.mtime_secs.to_le_bytes())?;
+        out.write_all(&fp.mtime_nanos.to_le_bytes())?;
+        let flags = tag.bits() | if wide { FLAG_WIDE } else { 0 };
+        out.write_all(&flags.to_le_bytes())?;
+        out.write_all(&fp.hash.to_le_bytes())?;
+        out.write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        for (start, len) in self.offsets.iter() {
+            if wide {
+                out.write_all(&start.to_le_bytes())?;
+                out.write_all(&len.to_le_bytes())?;
+            } else {
+                out.write_all(&(start as u32).to_le_bytes())?;
+                out.write_all(&(len as u32).to_le_bytes())?;
+            }
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&tmp_path, index_path)?;
+        Ok(())
+    }
+
+    /// Rouvre `source` en mmappant à la fois les données et le sidecar `index_path`.
+    ///
+    /// Échoue avec [`ParseError::Sidecar`] si le sidecar est absent du format attendu,
+    /// a été produit avec un autre découpage que `tag`, ou ne correspond plus au
+    /// fichier (taille, mtime ou empreinte).
+    pub fn open_indexed(
+        source: &Path,
+        index_path: &Path,
+        tag: IndexTag,
+    ) -> Result<Document, ParseError> {
+        let index_file = File::open(index_path)?;
+        let index_map = Arc::new(unsafe { MmapOptions::new().map(&index_file)? });
+        let header = &index_map[..];
+        if header.len() < HEADER_LEN || &header[0..8] != MAGIC {
+            return Err(ParseError::Sidecar(format!(
+                "{} n'est pas un index valide",
+                index_path.display()
+            )));
+        }
+
+        let flags = le_u32(header, 28);
+        if flags & !FLAG_WIDE != tag.bits() {
+            return Err(ParseError::Sidecar(format!(
+                "{} a été produit par un autre parser ou d'autres options",
+                index_path.display()
+            )));
+        }
+
+        let file = File::open(source)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        let stored = Fingerprint {
+            size: le_u64(header, 8),
+            mtime_secs: le_u64(header, 16),
+            mtime_nanos: le_u32(header, 24),
+            hash: le_u64(header, 32),
+        };
+        if Fingerprint::of(source, &mmap)? != stored {
+            return Err(ParseError::Sidecar(format!(
+                "{} est périmé pour {}",
+                index_path.display(),
+                source.display()
+            )));
+        }
+
+        let wide = flags & FLAG_WIDE != 0;
+        let count = le_u64(header, 40) as usize;
+        let expected = count
+            .checked_mul(MappedIndex::entry_size(wide))
+            .and_then(|n| n.checked_add(HEADER_LEN));
+        if expected != Some(header.len()) {
+            return Err(ParseError::Sidecar(format!(
+                "{} est tronqué",
+                index_path.display()
+            )));
+        }
+
+        // Ni l'UTF-8 ni les bornes des entrées ne sont garanties : vérification à
+        // l'accès (`Document::slice`), l'ouverture reste en O(1).
+        let offsets = LineIndex::Mapped(MappedIndex::new(index_map, HEADER_LEN, count, wide));
+        Ok(Document::new(DocumentData::Mmap(Arc::new(mmap)), offsets))
+    }
+}
@@ -0,0 +1,269 @@
+//! Document à index creux (une ligne indexée sur `stride`).
+//!
+//! - Mémoire d'index divisée par `stride`
+//! - Numérotation globale : les points de reprise sont les lignes 0, stride, 2·stride…
+//! - Toute ligne reste accessible : scan depuis le point de reprise précédent
+//! - Mode CSV : un terminateur entre guillemets ne termine pas l'enregistrement
+
+use crate::chunks::{aligned_chunks, check_utf8_lines, line_at_with, skip_lines_with};
+use crate::options::{LineTerminator, Validation};
+use crate::{encoding, DocumentData, LineIndex, ParseError, ParseOptions};
+use memchr::{memchr2_iter, memchr_iter};
+use rayon::prelude::*;
+
+/// Document indexé partiellement, mais adressable ligne à ligne.
+pub struct SparseDocument {
+    /// Garde le backend vivant.
+    pub data: DocumentData,
+    stride: usize,
+    /// Offset de début des lignes 0, stride, 2·stride…
+    checkpoints: Vec<u64>,
+    line_count: usize,
+    terminator: LineTerminator,
+    /// Guillemet des enregistrements CSV ; `None` : lignes brutes.
+    quote: Option<u8>,
+    /// Vrai si l'UTF-8 de chaque ligne, telle que découpée ici, a été vérifié à la
+    /// construction.
+    verified: bool,
+}
+
+/// Enregistrement CSV commençant à `pos` (hors guillemets), comme [`line_at_with`].
+fn record_at(data: &[u8], pos: usize, terminator: LineTerminator, quote: u8) -> (usize, usize) {
+    let mut inside = false;
+    for p in memchr2_iter(terminator.byte(), quote, &data[pos..]) {
+        if data[pos + p] == quote {
+            inside = !inside;
+        } else if !inside {
+            // Même convention que `line_at_with` : `\r` final retiré en mode `Auto`
+            let end = pos + p;
+            let len = if terminator.strips_cr() && end > pos && data[end - 1] == b'\r' {
+                p - 1
+            } else {
+                p
+            };
+            return (len, end + 1);
+        }
+    }
+    (data.len() - pos, data.len())
+}
+
+/// Nombre de lignes d'un chunk aligné sur `terminator`.
+fn count_lines(slice: &[u8], terminator: u8) -> usize {
+    let newlines = memchr_iter(terminator, slice).count();
+    newlines + usize::from(slice.last().is_some_and(|&b| b != terminator))
+}
+
+impl SparseDocument {
+    /// Construit l'index creux en parallèle (chunks d'environ `chunk_size` octets).
+    pub fn build(data: DocumentData, stride: usize, chunk_size: usize) -> Result<Self, ParseError> {
+        Self::build_with(data, stride, &ParseOptions::new().chunk_size(chunk_size))
+    }
+
+    /// Comme [`SparseDocument::build`], selon `opts` : taille de chunk, pool,
+    /// terminateur, validation et encodage (BOM ignoré, autres encodages transcodés).
+    ///
+    /// Deux passes : comptage des lignes par chunk, puis relevé des points de reprise
+    /// à partir de la numérotation globale. L'UTF-8 n'est vérifié d'avance qu'en
+    /// validation `Strict` ; sinon chaque ligne est vérifiée à l'accès (un index creux
+    /// ne peut ni retirer ni marquer de lignes).
+    pub fn build_with(
+        data: DocumentData,
+        stride: usize,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let stride = stride.max(1);
+        let terminator = opts.line_terminator;
+        let verified = opts.validation == Validation::Strict;
+        let byte = terminator.byte();
+        let (data, bom) = opts.install(|| {
+            encoding::to_utf8(
+                data,
+                opts.encoding,
+                opts.detect_encoding,
+                opts.chunk_size,
+                verified,
+            )
+        })?;
+        let bytes = data.as_bytes();
+        let (checkpoints, line_count) = opts.install(|| {
+            let chunks: Vec<_> = aligned_chunks(&bytes[bom..], opts.chunk_size, byte)
+                .into_iter()
+                .map(|(s, e)| (s + bom, e + bom))
+                .collect();
+
+            // 1) Lignes par chunk (les chunks finissent après un terminateur : validables séparément)
+            let counts: Vec<usize> = chunks
+                .par_iter()
+                .map(|&(s, e)| {
+                    if verified {
+                        check_utf8_lines(&bytes[s..e], terminator)?;
+                    }
+                    Ok(count_lines(&bytes[s..e], byte))
+                })
+                .collect::<Result<_, ParseError>>()?;
+
+            // 2) Numéro global de la première ligne de chaque chunk
+            let mut bases = Vec::with_capacity(counts.len());
+            let mut line_count = 0;
+            for &c in &counts {
+                bases.push(line_count);
+                line_count += c;
+            }
+
+            // 3) Points de reprise : lignes dont le numéro global est multiple de `stride`
+            let sets: Vec<Vec<u64>> = chunks
+                .par_iter()
+                .zip(bases.par_iter().zip(counts.par_iter()))
+                .map(|(&(s, _), (&base, &count))| {
+                    let mut local = Vec::with_capacity(count / stride + 1);
+                    let mut line = (stride - base % stride) % stride;
+                    if line >= count {
+                        return local;
+                    }
+                    let mut pos = skip_lines_with(bytes, s, line, terminator);
+                    loop {
+                        local.push(pos as u64);
+                        line += stride;
+                        if line >= count {
+                            return local;
+                        }
+                        pos = skip_lines_with(bytes, pos, stride, terminator);
+                    }
+                })
+                .collect();
+            Ok::<_, ParseError>((sets.concat(), line_count))
+        })?;
+
+        Ok(SparseDocument {
+            data,
+            stride,
+            checkpoints,
+            line_count,
+            terminator,
+            quote: None,
+            verified,
+        })
+    }
+
+    /// Index creux d'enregistrements CSV, à partir de l'index complet `records`
+    /// (calculé sur `data`, déjà en UTF-8 et BOM exclu, avec le terminateur de `opts`
+    /// par un indexeur tenant compte des guillemets).
+    ///
+    /// Seul un enregistrement sur `stride` est conservé ; les autres sont retrouvés
+    /// par un scan qui suit les guillemets `quote`. Validation comme
+    /// [`SparseDocument::build_with`].
+    pub fn from_records(
+        data: DocumentData,
+        records: &LineIndex,
+        stride: usize,
+        quote: u8,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let stride = stride.max(1);
+        let terminator = opts.line_terminator;
+        let verified = opts.validation == Validation::Strict;
+        if verified {
+            // Enregistrement par enregistrement : un terminateur non ASCII entre guillemets
+            // peut appartenir à un caractère.
+            let bytes = data.as_bytes();
+            opts.install(|| {
+                (0..records.len())
+                    .into_par_iter()
+                    .with_min_len(4096)
+                    .try_for_each(|i| {
+                        let (start, len) = records.get(i).unwrap();
+                        std::str::from_utf8(&bytes[start as usize..(start + len) as usize])
+                            .map(drop)
+                    })
+            })?;
+        }
+        let checkpoints = records.iter().step_by(stride).map(|(s, _)| s).collect();
+        Ok(SparseDocument {
+            data,
+            stride,
+            checkpoints,
+            line_count: records.len(),
+            terminator,
+            quote: Some(quote),
+            verified,
+        })
+    }
+
+    /// Une ligne sur `stride` est indexée.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Nombre de points de reprise en mémoire.
+    pub fn checkpoint_count(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Nombre total de lignes (indexées ou non).
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// (longueur, début du suivant) de la ligne ou de l'enregistrement en `pos`.
+    fn entry_at(&self, data: &[u8], pos: usize) -> (usize, usize) {
+        match self.quote {
+            Some(quote) => record_at(data, pos, self.terminator, quote),
+            None => line_at_with(data, pos, self.terminator),
+        }
+    }
+
+    /// Début de la `k`-ième entrée après celle qui commence à `pos`.
+    fn skip(&self, data: &[u8], pos: usize, k: usize) -> usize {
+        match self.quote {
+            Some(_) => (0..k).fold(pos, |pos, _| self.entry_at(data, pos).1),
+            None => skip_lines_with(data, pos, k, self.terminator),
+        }
+    }
+
+    /// Texte de l'entrée de longueur `len` en `start`, vérifié si besoin.
+    fn text<'a>(&self, data: &'a [u8], start: usize, len: usize) -> Result<&'a str, ParseError> {
+        let bytes = &data[start..start + len];
+        if self.verified {
+            // Safety: chaque ligne (ou enregistrement) validée telle quelle à la construction
+            // (`check_utf8_lines`) ; retirer un `\r` final la laisse valide.
+            Ok(unsafe { std::str::from_utf8_unchecked(bytes) })
+        } else {
+            std::str::from_utf8(bytes).map_err(ParseError::Utf8)
+        }
+    }
+
+    /// Accès à n'importe quelle ligne, par scan depuis le point de reprise précédent.
+    pub fn get_line(&self, idx: usize) -> Result<&str, ParseError> {
+        if idx >= self.line_count {
+            return Err(ParseError::Index(idx));
+        }
+        let data = self.data.as_bytes();
+        let from = self.checkpoints[idx / self.stride] as usize;
+        let start = self.skip(data, from, idx % self.stride);
+        let (len, _) = self.entry_at(data, start);
+        self.text(data, start, len)
+    }
+
+    /// Itérateur séquentiel sur toutes les lignes.
+    pub fn lines(&self) -> impl Iterator<Item = Result<&str, ParseError>> {
+        let data = self.data.as_bytes();
+        // Première ligne après l'éventuel BOM.
+        let mut pos = self.checkpoints.first().map_or(0, |&s| s as usize);
+        (0..self.line_count).map(move |_| {
+            let (len, next) = self.entry_at(data, pos);
+            let line = self.text(data, pos, len);
+            pos = next;
+            line
+        })
+    }
+
+    /// Itérateur sur les seules lignes indexées (0, stride, 2·stride…), sans scan.
+    pub fn checkpoint_lines(&self) -> impl Iterator<Item = Result<&str, ParseError>> {
+        let data = self.data.as_bytes();
+        self.checkpoints.iter().map(move |&start| {
+            let start = start as usize;
+            let (len, _) = self.entry_at(data, start);
+            self.text(data, start, len)
+        })
+    }
+}
@@ -0,0 +1,61 @@
+//! Lecture en flux depuis n'importe quel `Read` (stdin, pipe, socket…).
+//!
+//! - Mémoire bornée : une seule ligne en mémoire à la fois
+//! - Même découpage que les parsers indexés (`\n`, `\r` final retiré)
+
+use crate::ParseError;
+use std::io::{BufRead, BufReader, Read};
+
+/// Itérateur de lignes UTF-8 sur un lecteur, sans tout charger en mémoire.
+pub struct LineStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> LineStream<R> {
+    /// Flux de lignes sur un lecteur déjà bufferisé.
+    pub fn new(reader: R) -> Self {
+        LineStream {
+            reader,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<R: Read> LineStream<BufReader<R>> {
+    /// Flux de lignes sur un lecteur brut (ajoute un `BufReader`).
+    pub fn from_reader(reader: R) -> Self {
+        Self::new(BufReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Iterator for LineStream<R> {
+    type Item = Result<String, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        match self.reader.read_until(b'\n', &mut self.buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if self.buf.last() == Some(&b'\n') {
+                    self.buf.pop();
+                    if self.buf.last() == Some(&b'\r') {
+                        self.buf.pop();
+                    }
+                }
+                Some(
+                    String::from_utf8(std::mem::take(&mut self.buf))
+                        .map_err(|e| ParseError::Utf8(e.utf8_error())),
+                )
+            }
+            Err(e) => Some(Err(ParseError::Io(e))),
+        }
+    }
+}
+
+/// Lit tout le contenu de `reader` en mémoire (pour un `Document` en buffer).
+pub fn read_all<R: Read>(mut reader: R) -> Result<Vec<u8>, ParseError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
@@ -1,77 +1,79 @@
-use memchr::memchr_iter;
-use memmap2::MmapOptions;
-use parser_core::{Document, DocumentData, DocumentParser, ParseError};
-use rayon::prelude::*;
-use std::{fs::File, path::Path, sync::Arc};
+use parser_core::chunks;
+use parser_core::decompress;
+use parser_core::options::LineTerminator;
+use parser_core::{
+    Document, DocumentData, DocumentParser, LazyDocument, LineIndex, LineStream, ParseError,
+    ParseOptions, SparseDocument,
+};
+use std::io::{BufReader, Read};
+use std::{path::Path, sync::Arc};
 
 pub struct TxtParser;
 
-impl DocumentParser for TxtParser {
-    fn parse(path: &Path) -> Result<Document, ParseError> {
-        // 1) Memory-map en lecture seule
-        let file = File::open(path)?;
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let data = &mmap[..];
-        let len = data.len();
+impl TxtParser {
+    /// Indexe les lignes de `data` en parallèle, par chunks d'environ `chunk_size` octets.
+    ///
+    /// Les chunks sont alignés sur `\n` : le résultat est identique à un scan séquentiel,
+    /// quelle que soit la taille de chunk.
+    pub fn index_lines(data: &[u8], chunk_size: usize) -> LineIndex {
+        chunks::index_lines(data, chunk_size, LineTerminator::Auto)
+    }
+
+    /// Lecture ligne à ligne d'un flux (stdin, pipe…) en mémoire bornée.
+    pub fn stream_reader<R: Read>(reader: R) -> LineStream<BufReader<R>> {
+        LineStream::from_reader(reader)
+    }
+
+    /// Parse en n'indexant qu'une ligne sur `stride` ; les autres restent accessibles
+    /// par scan depuis la ligne indexée précédente.
+    ///
+    /// Taille de chunk, pool, validation, terminateur, encodage et madvise viennent
+    /// de `opts`.
+    pub fn parse_with_partial_index(
+        path: &Path,
+        stride: usize,
+        opts: &ParseOptions,
+    ) -> Result<SparseDocument, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        SparseDocument::build_with(backing, stride, opts).map_err(|e| e.with_path(path))
+    }
 
-        // 2) Conseille le kernel pour un readahead séquentiel
-        #[cfg(unix)]
-        unsafe {
-            libc::posix_madvise(
-                data.as_ptr() as *mut _,
-                len as libc::size_t,
-                libc::POSIX_MADV_SEQUENTIAL,
-            );
-        }
+    /// Ouvre un fichier en indexation paresseuse : rien n'est scanné avant le premier accès,
+    /// puis un point de reprise est gardé toutes les `checkpoint_every` lignes.
+    ///
+    /// `opts.chunk_size` fixe la granularité de l'indexation (des chunks petits, comme
+    /// [`parser_core::lazy::DEFAULT_LAZY_CHUNK_SIZE`], limitent le scan au premier accès).
+    /// Une entrée dans un autre encodage que l'UTF-8 est transcodée à l'ouverture.
+    pub fn parse_lazy(
+        path: &Path,
+        checkpoint_every: usize,
+        opts: &ParseOptions,
+    ) -> Result<LazyDocument, ParseError> {
+        let backing = decompress::open_data(path)?;
+        opts.madvise.apply(&backing);
+        LazyDocument::with_options(backing, checkpoint_every, opts).map_err(|e| e.with_path(path))
+    }
+}
 
-        // 3) Découpe en chunks de 64 MiB
-        const CHUNK_SIZE: usize = 64 * 1024 * 1024;
-        let boundaries: Vec<(usize, usize)> = (0..len)
-            .step_by(CHUNK_SIZE)
-            .map(|start| (start, (start + CHUNK_SIZE).min(len)))
-            .collect();
+impl DocumentParser for TxtParser {
+    const INDEX_KIND: u8 = b't';
 
-        // 4) Scan parallèle, collecte offsets (memchr_iter)
-        let sets: Vec<Vec<(u32, u32)>> = boundaries
-            .into_par_iter()
-            .map(|(s, e)| {
-                let slice = &data[s..e];
-                let mut local = Vec::with_capacity(slice.len() / 40 + 1); // estimation plus large
-                let mut prev = 0;
-                for nl in memchr_iter(b'\n', slice) {
-                    // On retire le \n du calcul de longueur
-                    let line_end = if nl > 0 && slice[nl - 1] == b'\r' {
-                        nl - 1
-                    } else {
-                        nl
-                    };
-                    local.push(((s + prev) as u32, (line_end - prev) as u32));
-                    prev = nl + 1;
-                }
-                if prev < slice.len() {
-                    local.push(((s + prev) as u32, (slice.len() - prev) as u32));
-                }
-                local
-            })
-            .collect();
+    fn parse_with(path: &Path, opts: &ParseOptions) -> Result<Document, ParseError> {
+        // 1) Memory-map en lecture seule (buffer décompressé si gzip/zstd/bzip2/xz)
+        let backing = decompress::open_data(path)?;
 
-        // 5) Concaténation efficace
-        let total: usize = sets.iter().map(Vec::len).sum();
-        let mut offsets = Vec::with_capacity(total);
-        for v in sets {
-            offsets.extend(v);
-        }
+        // 2) Conseil d'accès au kernel (readahead séquentiel par défaut)
+        opts.madvise.apply(&backing);
 
-        // 6) Vérification explicite UTF-8 sur toutes les lignes (robustesse)
-        for &(start, len) in &offsets {
-            let slice = &data[start as usize..(start + len) as usize];
-            std::str::from_utf8(slice)?;
-        }
+        // 3) Scan parallèle des chunks alignés sur les fins de ligne, puis validation
+        Document::from_data(backing, opts)
+            .map(|doc| doc.with_path(path))
+            .map_err(|e| e.with_path(path))
+    }
 
-        Ok(Document {
-            data: DocumentData::Mmap(Arc::new(mmap)),
-            offsets,
-        })
+    fn parse_bytes_with(data: Vec<u8>, opts: &ParseOptions) -> Result<Document, ParseError> {
+        Document::from_data(DocumentData::Buffer(Arc::new(data)), opts)
     }
 }
 
@@ -0,0 +1,152 @@
+use parser_core::decompress::{decompress, decompress_reader, inner_extension, Compression};
+use parser_core::{DocumentData, DocumentParser, ParseOptions};
+use std::io::{Read, Write};
+use tempfile::tempdir;
+use txt_parser::TxtParser;
+
+const TEXT: &[u8] = b"ligne1\nligne2\r\nligne3";
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
+
+fn bzip2(data: &[u8]) -> Vec<u8> {
+    let mut enc = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
+
+fn xz(data: &[u8]) -> Vec<u8> {
+    let mut enc = liblzma::write::XzEncoder::new(Vec::new(), 6);
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
+
+/// Bloc BGZF : membre gzip dont le champ extra `BC` donne la taille totale.
+fn bgzf_block(data: &[u8]) -> Vec<u8> {
+    let mut deflate =
+        flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    deflate.write_all(data).unwrap();
+    let cdata = deflate.finish().unwrap();
+    let mut crc = flate2::Crc::new();
+    crc.update(data);
+    let bsize = (18 + cdata.len() + 8 - 1) as u16;
+    let mut block = vec![
+        0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 6, 0, b'B', b'C', 2, 0,
+    ];
+    block.extend_from_slice(&bsize.to_le_bytes());
+    block.extend_from_slice(&cdata);
+    block.extend_from_slice(&crc.sum().to_le_bytes());
+    block.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    block
+}
+
+#[test]
+fn test_detect_magic_bytes() {
+    assert_eq!(Compression::detect(&gzip(TEXT)), Compression::Gzip);
+    assert_eq!(
+        Compression::detect(&zstd::encode_all(TEXT, 3).unwrap()),
+        Compression::Zstd
+    );
+    assert_eq!(Compression::detect(&bzip2(TEXT)), Compression::Bzip2);
+    assert_eq!(Compression::detect(&xz(TEXT)), Compression::Xz);
+    assert_eq!(Compression::detect(TEXT), Compression::None);
+    // Du texte qui commence par "BZh" n'est pas du bzip2.
+    assert_eq!(Compression::detect(b"BZh9 texte brut"), Compression::None);
+}
+
+#[test]
+fn test_parse_compressed_files_transparently() {
+    let dir = tempdir().unwrap();
+    let inputs = [
+        ("f.txt.gz", gzip(TEXT)),
+        ("f.txt.zst", zstd::encode_all(TEXT, 3).unwrap()),
+        ("f.txt.bz2", bzip2(TEXT)),
+        ("f.txt.xz", xz(TEXT)),
+    ];
+    for (name, bytes) in inputs {
+        let path = dir.path().join(name);
+        std::fs::write(&path, bytes).unwrap();
+        let doc = TxtParser::parse(&path).unwrap();
+        assert!(matches!(doc.data, DocumentData::Buffer(_)), "{name}");
+        assert_eq!(
+            doc.lines().collect::<Vec<_>>(),
+            vec!["ligne1", "ligne2", "ligne3"],
+            "{name}"
+        );
+        let lazy = TxtParser::parse_lazy(&path, 2, &ParseOptions::default()).unwrap();
+        assert_eq!(lazy.get_line(2).unwrap(), "ligne3", "{name}");
+    }
+}
+
+#[test]
+fn test_decompress_reader_streams_and_passes_through() {
+    let mut out = Vec::new();
+    decompress_reader(&xz(TEXT)[..])
+        .unwrap()
+        .read_to_end(&mut out)
+        .unwrap();
+    assert_eq!(out, TEXT);
+
+    // Entrée non compressée plus courte que l'en-tête magique : rendue telle quelle.
+    let mut out = Vec::new();
+    decompress_reader(&b"ab"[..])
+        .unwrap()
+        .read_to_end(&mut out)
+        .unwrap();
+    assert_eq!(out, b"ab");
+
+    let lines: Vec<String> = TxtParser::stream_reader(decompress_reader(&gzip(TEXT)[..]).unwrap())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(lines, vec!["ligne1", "ligne2", "ligne3"]);
+}
+
+#[test]
+fn test_decompress_multi_member_in_parallel() {
+    let parts: Vec<Vec<u8>> = (0..8)
+        .map(|i| format!("bloc{i}\n").repeat(100).into_bytes())
+        .collect();
+    let expected = parts.concat();
+
+    // zstd : une frame par partie
+    let frames: Vec<u8> = parts
+        .iter()
+        .flat_map(|p| zstd::encode_all(&p[..], 3).unwrap())
+        .collect();
+    assert_eq!(decompress(&frames, Compression::Zstd).unwrap(), expected);
+
+    // zstd avec taille de contenu : décompression directe dans le buffer final
+    let sized: Vec<u8> = parts
+        .iter()
+        .flat_map(|p| zstd::bulk::compress(p, 3).unwrap())
+        .collect();
+    assert_eq!(decompress(&sized, Compression::Zstd).unwrap(), expected);
+
+    // BGZF : un bloc par partie, plus le bloc vide de fin de fichier
+    let mut bgzf: Vec<u8> = parts.iter().flat_map(|p| bgzf_block(p)).collect();
+    bgzf.extend(bgzf_block(b""));
+    assert_eq!(decompress(&bgzf, Compression::Gzip).unwrap(), expected);
+
+    // Taille annoncée (ISIZE) fausse : erreur plutôt que données tronquées
+    let mut lying = bgzf.clone();
+    let first = bgzf_block(&parts[0]).len();
+    lying[first - 4..first].copy_from_slice(&10u32.to_le_bytes());
+    assert!(decompress(&lying, Compression::Gzip).is_err());
+
+    // gzip multi-membres classique : décompression séquentielle
+    let members: Vec<u8> = parts.iter().flat_map(|p| gzip(p)).collect();
+    assert_eq!(decompress(&members, Compression::Gzip).unwrap(), expected);
+}
+
+#[test]
+fn test_inner_extension_skips_compression_suffix() {
+    use std::path::Path;
+    assert_eq!(inner_extension(Path::new("data.csv")), "csv");
+    assert_eq!(inner_extension(Path::new("dir/Data.CSV.GZ")), "csv");
+    assert_eq!(inner_extension(Path::new("events.jsonl.zst")), "jsonl");
+    assert_eq!(inner_extension(Path::new("archive.gz")), "");
+    assert_eq!(inner_extension(Path::new("README")), "");
+}
@@ -1,6 +1,13 @@
-use parser_core::{Document, DocumentParser};
+use parser_core::encoding::Encoding;
+use parser_core::options::{LineTerminator, Validation};
+use parser_core::{
+    sidecar, Document, DocumentData, DocumentParser, LazyDocument, LineIndex, ParseError,
+    ParseOptions, SparseDocument,
+};
+use proptest::prelude::*;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
+use std::sync::Arc;
 use tempfile::tempdir;
 use txt_parser::TxtParser;
 
@@ -73,3 +80,496 @@ fn test_parse_txt_file_not_found() {
     let res = TxtParser::parse(path);
     assert!(res.is_err());
 }
+
+#[test]
+fn test_parse_txt_offsets_beyond_4gib() {
+    // Fichier creux : seules quelques lignes sont réellement écrites.
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("sparse.txt");
+    let mut file = File::create(&file_path).unwrap();
+    let far = (1u64 << 32) + 1024;
+    file.write_all(b"debut\n").unwrap();
+    file.seek(SeekFrom::Start(far - 1)).unwrap();
+    file.write_all(b"\nloin1\r\nloin2\nfin").unwrap();
+    drop(file);
+
+    let doc = TxtParser::parse(&file_path).unwrap();
+    assert!(doc.offsets.is_wide());
+    assert_eq!(doc.line_count(), 5);
+    assert_eq!(doc.get_line(0).unwrap(), "debut");
+    // La ligne de zéros traverse tous les chunks et dépasse elle-même u32::MAX octets.
+    assert_eq!(doc.offsets.get(1), Some((6, far - 1 - 6)));
+    assert_eq!(doc.offsets.get(2), Some((far, 5)));
+    assert_eq!(doc.get_line(2).unwrap(), "loin1");
+    assert_eq!(doc.get_line(3).unwrap(), "loin2");
+    assert_eq!(
+        doc.lines_range(2, 5).unwrap(),
+        vec!["loin1", "loin2", "fin"]
+    );
+    assert_eq!(doc.lines().last(), Some("fin"));
+}
+
+/// Scan séquentiel de référence : une ligne par \n, \r final retiré.
+fn sequential_offsets(data: &[u8]) -> Vec<(u64, u64)> {
+    let mut out = Vec::new();
+    let mut prev = 0;
+    for (i, &b) in data.iter().enumerate() {
+        if b == b'\n' {
+            let end = if i > prev && data[i - 1] == b'\r' {
+                i - 1
+            } else {
+                i
+            };
+            out.push((prev as u64, (end - prev) as u64));
+            prev = i + 1;
+        }
+    }
+    if prev < data.len() {
+        out.push((prev as u64, (data.len() - prev) as u64));
+    }
+    out
+}
+
+#[test]
+fn test_index_lines_crlf_split_at_chunk_boundary() {
+    // Le \r est le dernier octet du premier chunk nominal, le \n le premier du suivant.
+    let data = b"abc\r\ndef\r\nghi";
+    let idx = TxtParser::index_lines(data, 4);
+    assert_eq!(
+        idx.iter().collect::<Vec<_>>(),
+        vec![(0, 3), (5, 3), (10, 3)]
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_index_lines_matches_sequential(
+        data in proptest::collection::vec(
+            prop_oneof![Just(b'\n'), Just(b'\r'), Just(b'a'), any::<u8>()],
+            0..512,
+        ),
+        chunk_size in 1usize..64,
+    ) {
+        let idx = TxtParser::index_lines(&data, chunk_size);
+        prop_assert_eq!(idx.iter().collect::<Vec<_>>(), sequential_offsets(&data));
+    }
+}
+
+#[test]
+fn test_parse_indexed_reuses_and_invalidates_sidecar() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("log.txt");
+    std::fs::write(&file_path, "ligne1\r\nligne2\nligne3").unwrap();
+    let index_path = sidecar::default_index_path(&file_path);
+    let tag = TxtParser::index_tag(&ParseOptions::default());
+
+    let first = TxtParser::parse_indexed(&file_path).unwrap();
+    assert!(index_path.exists());
+    assert!(!matches!(first.offsets, LineIndex::Mapped(_)));
+
+    // Deuxième ouverture : index relu depuis le sidecar mmappé.
+    let second = TxtParser::parse_indexed(&file_path).unwrap();
+    assert!(matches!(second.offsets, LineIndex::Mapped(_)));
+    assert_eq!(second.offsets, first.offsets);
+    assert_eq!(second.get_line(1).unwrap(), "ligne2");
+    assert_eq!(
+        second.lines().collect::<Vec<_>>(),
+        vec!["ligne1", "ligne2", "ligne3"]
+    );
+
+    // Contenu modifié : le sidecar est rejeté puis régénéré.
+    std::fs::write(&file_path, "autre\ncontenu\n").unwrap();
+    assert!(Document::open_indexed(&file_path, &index_path, tag).is_err());
+    let third = TxtParser::parse_indexed(&file_path).unwrap();
+    assert_eq!(third.lines().collect::<Vec<_>>(), vec!["autre", "contenu"]);
+    let fourth = Document::open_indexed(&file_path, &index_path, tag).unwrap();
+    assert_eq!(fourth.get_line(1).unwrap(), "contenu");
+}
+
+#[test]
+fn test_open_indexed_rejects_garbage_sidecar() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("log.txt");
+    std::fs::write(&file_path, "a\nb\n").unwrap();
+    let index_path = sidecar::default_index_path(&file_path);
+    let tag = TxtParser::index_tag(&ParseOptions::default());
+    std::fs::write(&index_path, b"pas un index").unwrap();
+    assert!(matches!(
+        Document::open_indexed(&file_path, &index_path, tag),
+        Err(ParseError::Sidecar(_))
+    ));
+    // parse_indexed retombe sur un parsing complet et réécrit le sidecar.
+    let doc = TxtParser::parse_indexed(&file_path).unwrap();
+    assert_eq!(doc.line_count(), 2);
+    assert!(Document::open_indexed(&file_path, &index_path, tag).is_ok());
+}
+
+#[test]
+fn test_open_indexed_reports_out_of_bounds_entry_on_access() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("log.txt");
+    std::fs::write(&file_path, "a\nb\n").unwrap();
+    TxtParser::parse_indexed(&file_path).unwrap();
+    let index_path = sidecar::default_index_path(&file_path);
+    let tag = TxtParser::index_tag(&ParseOptions::default());
+    // Longueur de la deuxième entrée (32 bits) : bien au-delà de la fin du fichier.
+    let mut index = std::fs::read(&index_path).unwrap();
+    index[60..64].copy_from_slice(&1000u32.to_le_bytes());
+    std::fs::write(&index_path, index).unwrap();
+    // Ouverture en O(1) : l'entrée fautive n'est signalée qu'à l'accès.
+    let doc = Document::open_indexed(&file_path, &index_path, tag).unwrap();
+    assert_eq!(doc.get_line(0).unwrap(), "a");
+    assert!(matches!(doc.get_line(1), Err(ParseError::Sidecar(_))));
+    assert!(matches!(doc.get_line_lossy(1), Err(ParseError::Sidecar(_))));
+    assert_eq!(doc.lines_lossy().collect::<Vec<_>>(), vec!["a"]);
+}
+
+#[test]
+fn test_parse_indexed_ignores_sidecar_from_other_options() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("log.txt");
+    std::fs::write(&file_path, "a\r\nb\r\n").unwrap();
+    let index_path = sidecar::default_index_path(&file_path);
+    TxtParser::parse_indexed(&file_path).unwrap();
+
+    // Sidecar écrit en mode `Auto` : refusé pour `Lf`, puis remplacé.
+    let lf = ParseOptions::new().line_terminator(LineTerminator::Lf);
+    assert!(matches!(
+        Document::open_indexed(&file_path, &index_path, TxtParser::index_tag(&lf)),
+        Err(ParseError::Sidecar(_))
+    ));
+    let doc = TxtParser::parse_indexed_with(&file_path, &lf).unwrap();
+    assert_eq!(doc.get_line(0).unwrap(), "a\r");
+    let reopened = TxtParser::parse_indexed_with(&file_path, &lf).unwrap();
+    assert!(matches!(reopened.offsets, LineIndex::Mapped(_)));
+    assert_eq!(reopened.get_line(1).unwrap(), "b\r");
+
+    // Un autre parser ne réutilise pas l'index.
+    let other = parser_core::IndexTag::new(b'c', &lf).quote(b'"');
+    assert!(Document::open_indexed(&file_path, &index_path, other).is_err());
+}
+
+#[test]
+fn test_parse_lazy_indexes_on_demand() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lazy.txt");
+    let mut file = File::create(&file_path).unwrap();
+    for i in 0..1000 {
+        writeln!(file, "ligne{i}").unwrap();
+    }
+    drop(file);
+
+    let opts = ParseOptions::new().chunk_size(1024);
+    let doc = TxtParser::parse_lazy(&file_path, 64, &opts).unwrap();
+    assert_eq!(doc.indexed_chunks(), 0);
+    assert_eq!(doc.get_line(0).unwrap(), "ligne0");
+    assert_eq!(doc.indexed_chunks(), 1);
+    assert_eq!(doc.get_line(777).unwrap(), "ligne777");
+    // Retour en arrière : le préfixe déjà indexé suffit.
+    let indexed = doc.indexed_chunks();
+    assert_eq!(doc.get_line(300).unwrap(), "ligne300");
+    assert_eq!(doc.indexed_chunks(), indexed);
+    assert!(matches!(doc.get_line(1000), Err(ParseError::Index(1000))));
+    assert_eq!(doc.line_count().unwrap(), 1000);
+    assert_eq!(doc.lines().nth(999).unwrap().unwrap(), "ligne999");
+}
+
+#[test]
+fn test_lazy_document_touches_only_needed_chunks() {
+    let data = b"a\nb\nc\nd\ne\nf\n\xFF\n".to_vec();
+    let doc = LazyDocument::new(DocumentData::Buffer(Arc::new(data)), 4, 2).unwrap();
+    assert_eq!(doc.get_line(1).unwrap(), "b");
+    assert_eq!(doc.indexed_chunks(), 1);
+    // Le chunk invalide n'est découvert qu'au moment où on l'atteint.
+    assert_eq!(doc.get_line(5).unwrap(), "f");
+    assert!(matches!(doc.get_line(6), Err(ParseError::Utf8(_))));
+    assert!(doc.line_count().is_err());
+    assert!(doc.lines().any(|l| l.is_err()));
+}
+
+proptest! {
+    #[test]
+    fn prop_lazy_document_matches_full_index(
+        text in "[ab\r\né]{0,300}",
+        chunk_size in 1usize..48,
+        checkpoint_every in 1usize..8,
+    ) {
+        let data = text.into_bytes();
+        let full = TxtParser::index_lines(&data, data.len().max(1));
+        let expected: Vec<&str> = full
+            .iter()
+            .map(|(s, l)| std::str::from_utf8(&data[s as usize..(s + l) as usize]).unwrap())
+            .collect();
+        let doc = LazyDocument::new(
+            DocumentData::Buffer(Arc::new(data.clone())),
+            chunk_size,
+            checkpoint_every,
+        )
+        .unwrap();
+        for (i, line) in expected.iter().enumerate() {
+            prop_assert_eq!(doc.get_line(i).unwrap(), *line);
+        }
+        prop_assert!(doc.get_line(expected.len()).is_err());
+        prop_assert_eq!(doc.line_count().unwrap(), expected.len());
+        let lines: Vec<&str> = doc.lines().map(Result::unwrap).collect();
+        prop_assert_eq!(lines, expected);
+    }
+}
+
+#[test]
+fn test_parse_with_partial_index_reaches_every_line() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("stride.txt");
+    let mut file = File::create(&file_path).unwrap();
+    for i in 0..10 {
+        write!(file, "l{i}\r\n").unwrap();
+    }
+    drop(file);
+
+    let doc = TxtParser::parse_with_partial_index(&file_path, 4, &ParseOptions::default()).unwrap();
+    assert_eq!(doc.stride(), 4);
+    assert_eq!(doc.line_count(), 10);
+    assert_eq!(doc.checkpoint_count(), 3);
+    assert_eq!(doc.get_line(5).unwrap(), "l5");
+    assert_eq!(doc.get_line(9).unwrap(), "l9");
+    assert!(matches!(doc.get_line(10), Err(ParseError::Index(10))));
+    assert_eq!(
+        doc.checkpoint_lines()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+        vec!["l0", "l4", "l8"]
+    );
+}
+
+#[test]
+fn test_partial_and_lazy_index_honor_options() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("records.bin");
+    std::fs::write(&file_path, b"a\nb\0c\0\xFF\0d").unwrap();
+    let opts = ParseOptions::new()
+        .chunk_size(2)
+        .line_terminator(LineTerminator::Byte(0))
+        .validation(Validation::Off);
+
+    let sparse = TxtParser::parse_with_partial_index(&file_path, 2, &opts).unwrap();
+    assert_eq!(sparse.line_count(), 4);
+    assert_eq!(sparse.get_line(0).unwrap(), "a\nb");
+    assert_eq!(sparse.get_line(3).unwrap(), "d");
+    // Sans validation préalable, la ligne invalide n'échoue qu'à l'accès.
+    assert!(matches!(sparse.get_line(2), Err(ParseError::Utf8(_))));
+
+    let lazy = TxtParser::parse_lazy(&file_path, 2, &opts).unwrap();
+    assert_eq!(lazy.line_count().unwrap(), 4);
+    assert_eq!(lazy.get_line(1).unwrap(), "c");
+    assert!(matches!(lazy.get_line(2), Err(ParseError::Utf8(_))));
+    assert_eq!(lazy.get_line(3).unwrap(), "d");
+
+    let strict = opts.validation(Validation::Strict);
+    assert!(TxtParser::parse_with_partial_index(&file_path, 2, &strict).is_err());
+}
+
+#[test]
+fn test_partial_and_lazy_index_honor_encoding() {
+    let dir = tempdir().unwrap();
+    let bom_path = dir.path().join("bom.txt");
+    std::fs::write(&bom_path, b"\xEF\xBB\xBFun\ndeux\n").unwrap();
+    let latin1_path = dir.path().join("latin1.txt");
+    std::fs::write(&latin1_path, b"caf\xE9\nna\xEFf\n").unwrap();
+    let latin1 = ParseOptions::new().encoding(Encoding::Latin1);
+
+    let sparse = TxtParser::parse_with_partial_index(&bom_path, 1, &ParseOptions::new()).unwrap();
+    assert_eq!(sparse.get_line(0).unwrap(), "un");
+    assert_eq!(
+        sparse.lines().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec!["un", "deux"]
+    );
+    let lazy = TxtParser::parse_lazy(&bom_path, 1, &ParseOptions::new()).unwrap();
+    assert_eq!(lazy.get_line(0).unwrap(), "un");
+
+    let sparse = TxtParser::parse_with_partial_index(&latin1_path, 1, &latin1).unwrap();
+    assert_eq!(sparse.get_line(1).unwrap(), "naïf");
+    let lazy = TxtParser::parse_lazy(&latin1_path, 1, &latin1).unwrap();
+    assert_eq!(
+        lazy.lines().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec!["café", "naïf"]
+    );
+}
+
+#[test]
+fn test_non_ascii_terminator_validates_each_line() {
+    let dir = tempdir().unwrap();
+    let opts = ParseOptions::new().line_terminator(LineTerminator::Byte(0xA9));
+
+    // Lignes valides séparées par un octet qui n'est pas de l'UTF-8 seul
+    let valid = dir.path().join("valid.bin");
+    std::fs::write(&valid, b"a\xA9b").unwrap();
+    let sparse = TxtParser::parse_with_partial_index(&valid, 1, &opts).unwrap();
+    assert_eq!(sparse.get_line(1).unwrap(), "b");
+    let lazy = TxtParser::parse_lazy(&valid, 1, &opts).unwrap();
+    assert_eq!(lazy.get_line(0).unwrap(), "a");
+
+    // « é » (C3 A9) coupé par le terminateur : la ligne 0 n'est plus de l'UTF-8
+    let split = dir.path().join("split.bin");
+    std::fs::write(&split, "café".as_bytes()).unwrap();
+    assert!(TxtParser::parse_with_partial_index(&split, 1, &opts).is_err());
+    let lazy = TxtParser::parse_lazy(&split, 1, &opts).unwrap();
+    assert!(lazy.get_line(0).is_err());
+}
+
+proptest! {
+    #[test]
+    fn prop_sparse_document_matches_full_index(
+        text in "[ab\r\né]{0,300}",
+        chunk_size in 1usize..48,
+        stride in 1usize..8,
+    ) {
+        let data = text.into_bytes();
+        let full = TxtParser::index_lines(&data, data.len().max(1));
+        let expected: Vec<&str> = full
+            .iter()
+            .map(|(s, l)| std::str::from_utf8(&data[s as usize..(s + l) as usize]).unwrap())
+            .collect();
+        let doc = SparseDocument::build(
+            DocumentData::Buffer(Arc::new(data.clone())),
+            stride,
+            chunk_size,
+        )
+        .unwrap();
+        prop_assert_eq!(doc.line_count(), expected.len());
+        prop_assert_eq!(doc.checkpoint_count(), expected.len().div_ceil(stride));
+        for (i, line) in expected.iter().enumerate() {
+            prop_assert_eq!(doc.get_line(i).unwrap(), *line);
+        }
+        let lines: Vec<&str> = doc.lines().map(Result::unwrap).collect();
+        prop_assert_eq!(lines, expected);
+    }
+}
+
+#[test]
+fn test_parse_reader_and_stream_reader() {
+    let input: &[u8] = b"ligne1\r\nligne2\nligne3";
+    let doc = TxtParser::parse_reader(input).unwrap();
+    assert!(matches!(doc.data, DocumentData::Buffer(_)));
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["ligne1", "ligne2", "ligne3"]
+    );
+
+    let streamed: Vec<String> = TxtParser::stream_reader(input)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(streamed, vec!["ligne1", "ligne2", "ligne3"]);
+
+    let bad: &[u8] = b"ok\n\xFF\nok\n";
+    assert!(TxtParser::parse_reader(bad).is_err());
+    let results: Vec<_> = TxtParser::stream_reader(bad).collect();
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(ParseError::Utf8(_))));
+    assert_eq!(results[2].as_deref().unwrap(), "ok");
+}
+
+#[test]
+fn test_parse_with_options() {
+    use parser_core::options::{LineTerminator, Madvise, ParseOptions};
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("opts.txt");
+    let content: String = (0..1000).map(|i| format!("ligne {i}\r\n")).collect();
+    std::fs::write(&path, &content).unwrap();
+
+    let reference = TxtParser::parse(&path).unwrap();
+    let opts = ParseOptions::new()
+        .chunk_size(97)
+        .threads(2)
+        .madvise(Madvise::Random);
+    let doc = TxtParser::parse_with(&path, &opts).unwrap();
+    assert_eq!(doc.offsets, reference.offsets);
+
+    let lf = ParseOptions::new().line_terminator(LineTerminator::Lf);
+    let doc = TxtParser::parse_with(&path, &lf).unwrap();
+    assert_eq!(doc.get_line(3).unwrap(), "ligne 3\r");
+}
+
+#[test]
+fn test_parse_with_custom_terminator_and_max_length() {
+    use parser_core::options::{LineTerminator, ParseOptions};
+    let data = b"a\0bb\0ccc".to_vec();
+    let opts = ParseOptions::new().line_terminator(LineTerminator::Byte(0));
+    let doc = TxtParser::parse_bytes_with(data.clone(), &opts).unwrap();
+    assert_eq!(doc.lines().collect::<Vec<_>>(), vec!["a", "bb", "ccc"]);
+
+    let opts = opts.max_line_length(2);
+    assert!(matches!(
+        TxtParser::parse_bytes_with(data, &opts),
+        Err(ParseError::InvalidLine { .. })
+    ));
+}
+
+#[test]
+fn test_utf8_error_location() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("bad.txt");
+    std::fs::write(&path, b"ok\nencore ok\nbad \xFF here\nfin\n").unwrap();
+
+    let err = TxtParser::parse(&path).err().unwrap();
+    let loc = err.location().expect("erreur localisée");
+    assert_eq!(loc.path.as_deref(), Some(path.as_path()));
+    assert_eq!(loc.line, Some(2));
+    assert_eq!(loc.byte_offset, Some(17));
+    assert_eq!(loc.column, Some(5));
+    let msg = err.to_string();
+    assert!(msg.contains("line 3, column 5, byte 17"), "{msg}");
+    assert!(msg.contains("bad \u{FFFD} here"), "{msg}");
+}
+
+#[test]
+fn test_lossy_and_skip_validation() {
+    use parser_core::options::{ParseOptions, Validation};
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("latin1.log");
+    std::fs::write(&path, b"debut\ncaf\xE9\nmilieu\n\xFFfin\nfin\n").unwrap();
+
+    let lossy = ParseOptions::new().validation(Validation::Lossy);
+    let doc = TxtParser::parse_with(&path, &lossy).unwrap();
+    assert_eq!(doc.line_count(), 5);
+    assert_eq!(doc.invalid_lines(), &[1, 3]);
+    assert_eq!(doc.get_line_lossy(1).unwrap(), "caf\u{FFFD}");
+    assert!(matches!(
+        doc.get_line(1),
+        Err(ParseError::InvalidUtf8 { .. })
+    ));
+    assert_eq!(doc.get_line(2).unwrap(), "milieu");
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["debut", "milieu", "fin"]
+    );
+    assert_eq!(doc.lines_lossy().count(), 5);
+
+    let skip = ParseOptions::new().validation(Validation::Skip);
+    let doc = TxtParser::parse_with(&path, &skip).unwrap();
+    assert_eq!(
+        doc.lines().collect::<Vec<_>>(),
+        vec!["debut", "milieu", "fin"]
+    );
+    assert_eq!(doc.get_line(2).unwrap(), "fin");
+    let reported: Vec<_> = doc
+        .errors()
+        .iter()
+        .map(|e| {
+            let loc = e.location().unwrap();
+            (loc.line, loc.byte_offset, loc.path.clone())
+        })
+        .collect();
+    assert_eq!(
+        reported,
+        vec![
+            (Some(1), Some(9), Some(path.clone())),
+            (Some(3), Some(18), Some(path.clone())),
+        ]
+    );
+
+    // Sans validation : accès sûrs, les lignes invalides sont vérifiées à la lecture.
+    let off = ParseOptions::new().validation(Validation::Off);
+    let doc = TxtParser::parse_with(&path, &off).unwrap();
+    assert!(doc.get_line(3).is_err());
+    assert_eq!(doc.lines().count(), 3);
+}